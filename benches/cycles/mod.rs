@@ -0,0 +1,117 @@
+//! Criterion measurement reporting throughput in bytes per CPU cycle.
+//!
+//! Cycles are read from the time stamp counter on x86-64.
+//! Other architectures fall back to nanoseconds of wall time.
+
+use criterion::measurement::{Measurement, ValueFormatter};
+use criterion::Throughput;
+
+pub struct Cycles;
+
+#[cfg(target_arch = "x86_64")]
+const UNIT: &str = "cycles";
+#[cfg(not(target_arch = "x86_64"))]
+const UNIT: &str = "ns";
+
+#[cfg(target_arch = "x86_64")]
+fn counter() -> u64 {
+    use core::arch::x86_64::{_mm_lfence, _rdtsc};
+
+    // The fences prevent the counter from being read out of order
+    // with the code being measured.
+    unsafe {
+        _mm_lfence();
+        let value = _rdtsc();
+        _mm_lfence();
+        value
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn counter() -> u64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static START: OnceLock<Instant> = OnceLock::new();
+
+    START.get_or_init(Instant::now).elapsed().as_nanos() as u64
+}
+
+impl Measurement for Cycles {
+    type Intermediate = u64;
+    type Value = u64;
+
+    fn start(&self) -> Self::Intermediate {
+        counter()
+    }
+
+    fn end(&self, start: Self::Intermediate) -> Self::Value {
+        counter().saturating_sub(start)
+    }
+
+    fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+        v1 + v2
+    }
+
+    fn zero(&self) -> Self::Value {
+        0
+    }
+
+    fn to_f64(&self, value: &Self::Value) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        &CyclesFormatter
+    }
+}
+
+struct CyclesFormatter;
+
+impl CyclesFormatter {
+    fn per_unit(throughput: &Throughput) -> (f64, &'static str) {
+        match throughput {
+            Throughput::Bytes(bytes) | Throughput::BytesDecimal(bytes) => {
+                #[cfg(target_arch = "x86_64")]
+                let label = "bytes/cycle";
+                #[cfg(not(target_arch = "x86_64"))]
+                let label = "bytes/ns";
+
+                (*bytes as f64, label)
+            }
+            Throughput::Elements(elements) => {
+                #[cfg(target_arch = "x86_64")]
+                let label = "elem/cycle";
+                #[cfg(not(target_arch = "x86_64"))]
+                let label = "elem/ns";
+
+                (*elements as f64, label)
+            }
+        }
+    }
+}
+
+impl ValueFormatter for CyclesFormatter {
+    fn scale_values(&self, _typical_value: f64, _values: &mut [f64]) -> &'static str {
+        UNIT
+    }
+
+    fn scale_throughputs(
+        &self,
+        _typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let (amount, label) = Self::per_unit(throughput);
+
+        for value in values {
+            *value = amount / *value;
+        }
+
+        label
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        UNIT
+    }
+}
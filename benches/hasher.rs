@@ -0,0 +1,60 @@
+use std::hash::Hasher;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use mx3::Mx3Hasher;
+
+mod cycles;
+
+use cycles::Cycles;
+
+const STREAM_SIZE: usize = 1024 * 1024;
+
+const WRITE_SIZES: [usize; 5] = [1, 8, 64, 1024, 64 * 1024];
+
+fn bench_hasher(c: &mut Criterion<Cycles>) {
+    let mut group = c.benchmark_group("Mx3Hasher");
+    let buffer = vec![0xabu8; STREAM_SIZE];
+    group.throughput(Throughput::Bytes(STREAM_SIZE as u64));
+
+    for write_size in WRITE_SIZES {
+        group.bench_with_input(
+            BenchmarkId::new("write_size", write_size),
+            &buffer,
+            |b, input| {
+                b.iter(|| {
+                    let mut hasher = Mx3Hasher::new(1);
+
+                    for chunk in input.chunks(write_size) {
+                        hasher.write(chunk);
+                    }
+
+                    hasher.finish()
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_hasher_u64(c: &mut Criterion<Cycles>) {
+    let mut group = c.benchmark_group("Mx3Hasher");
+    group.throughput(Throughput::Bytes(8));
+
+    group.bench_function("write_u64", |b| {
+        b.iter(|| {
+            let mut hasher = Mx3Hasher::new(1);
+            hasher.write_u64(criterion::black_box(123456789));
+            hasher.finish()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().with_measurement(Cycles);
+    targets = bench_hasher, bench_hasher_u64
+);
+criterion_main!(benches);
@@ -0,0 +1,92 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use mx3::Version;
+use rand::RngCore;
+
+mod cycles;
+
+use cycles::Cycles;
+
+const VERSIONS: [Version; 3] = [Version::V1, Version::V2, Version::V3];
+
+const HASH_SIZES: [usize; 12] = [
+    0,
+    1,
+    7,
+    8,
+    16,
+    32,
+    64,
+    256,
+    1024,
+    16 * 1024,
+    256 * 1024,
+    1024 * 1024,
+];
+
+const FILL_SIZES: [usize; 4] = [8, 64, 1024, 64 * 1024];
+
+fn bench_mix(c: &mut Criterion<Cycles>) {
+    let mut group = c.benchmark_group("mix");
+    group.throughput(Throughput::Bytes(8));
+
+    for version in VERSIONS {
+        group.bench_function(BenchmarkId::from_parameter(version), |b| {
+            let mut x = 0u64;
+            b.iter(|| {
+                x = version.mix(criterion::black_box(x));
+                x
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_hash(c: &mut Criterion<Cycles>) {
+    let mut group = c.benchmark_group("hash");
+    let buffer = vec![0xabu8; *HASH_SIZES.last().unwrap()];
+
+    for size in HASH_SIZES {
+        if size == 0 {
+            group.throughput(Throughput::Elements(1));
+        } else {
+            group.throughput(Throughput::Bytes(size as u64));
+        }
+
+        for version in VERSIONS {
+            group.bench_with_input(
+                BenchmarkId::new(version.to_string(), size),
+                &buffer[0..size],
+                |b, input| b.iter(|| version.hash(criterion::black_box(input), 1)),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn bench_fill_bytes(c: &mut Criterion<Cycles>) {
+    let mut group = c.benchmark_group("fill_bytes");
+    let mut buffer = vec![0u8; *FILL_SIZES.last().unwrap()];
+
+    for size in FILL_SIZES {
+        group.throughput(Throughput::Bytes(size as u64));
+
+        for version in VERSIONS {
+            let mut rng = version.rng(1);
+
+            group.bench_function(BenchmarkId::new(version.to_string(), size), |b| {
+                b.iter(|| rng.fill_bytes(&mut buffer[0..size]))
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().with_measurement(Cycles);
+    targets = bench_mix, bench_hash, bench_fill_bytes
+);
+criterion_main!(benches);
@@ -0,0 +1,69 @@
+//! Generates the ziggurat tables used by the normal and exponential
+//! distributions.
+//!
+//! The tables are computed with `libm` rather than the `f64` methods of
+//! `std`, which use the platform's math library and may round differently
+//! between build hosts.
+
+use std::fmt::Write;
+use std::path::PathBuf;
+
+/// Number of layers of each ziggurat.
+const LAYERS: usize = 256;
+
+// Constants from "The Ziggurat Method for Generating Random Variables" by
+// Marsaglia and Tsang for 256 layers.
+const NORMAL_R: f64 = 3.654_152_885_361_009;
+const NORMAL_V: f64 = 0.004_928_673_233_99;
+const EXP_R: f64 = 7.697_117_470_131_05;
+const EXP_V: f64 = 0.003_949_659_822_581_557;
+
+/// Returns the boundaries of the layers and the density at each boundary.
+fn ziggurat(r: f64, v: f64, pdf: fn(f64) -> f64, inverse_pdf: fn(f64) -> f64) -> [Vec<f64>; 2] {
+    let mut x = vec![0.0; LAYERS + 1];
+    x[0] = v / pdf(r);
+    x[1] = r;
+
+    for i in 2..LAYERS {
+        x[i] = inverse_pdf(v / x[i - 1] + pdf(x[i - 1]));
+    }
+
+    let f = x.iter().map(|&x| pdf(x)).collect();
+
+    [x, f]
+}
+
+fn write_table(output: &mut String, name: &str, values: &[f64]) {
+    writeln!(output, "const {}: [f64; {}] = [", name, values.len()).unwrap();
+
+    for value in values {
+        writeln!(output, "    {:e},", value).unwrap();
+    }
+
+    writeln!(output, "];").unwrap();
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let mut output = String::new();
+
+    writeln!(output, "const ZIG_NORMAL_R: f64 = {:e};", NORMAL_R).unwrap();
+    writeln!(output, "const ZIG_EXP_R: f64 = {:e};", EXP_R).unwrap();
+
+    let [x, f] = ziggurat(
+        NORMAL_R,
+        NORMAL_V,
+        |x| libm::exp(-x * x / 2.0),
+        |y| libm::sqrt(-2.0 * libm::log(y)),
+    );
+    write_table(&mut output, "ZIG_NORMAL_X", &x);
+    write_table(&mut output, "ZIG_NORMAL_F", &f);
+
+    let [x, f] = ziggurat(EXP_R, EXP_V, |x| libm::exp(-x), |y| -libm::log(y));
+    write_table(&mut output, "ZIG_EXP_X", &x);
+    write_table(&mut output, "ZIG_EXP_F", &f);
+
+    let path = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("ziggurat_tables.rs");
+    std::fs::write(path, output).unwrap();
+}
@@ -0,0 +1,31 @@
+//! Generates the C header into `OUT_DIR`.
+//!
+//! The committed `include/mx3.h` is only overwritten when the
+//! `MX3_CAPI_UPDATE_HEADER` environment variable is set, so that builds do
+//! not modify the source tree. A test checks that it is up to date.
+
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed=MX3_CAPI_UPDATE_HEADER");
+
+    let header = out_dir.join("mx3.h");
+
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("unable to generate the header")
+        .write_to_file(&header);
+
+    if std::env::var_os("MX3_CAPI_UPDATE_HEADER").is_some() {
+        std::fs::copy(&header, crate_dir.join("include").join("mx3.h"))
+            .expect("unable to copy the header");
+    }
+}
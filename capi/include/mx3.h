@@ -0,0 +1,258 @@
+/* C API for the mx3 algorithm. Licensed under the MIT License. */
+
+#ifndef MX3_H
+#define MX3_H
+
+/* This file is generated by cbindgen from the mx3-capi crate. Do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Hasher for computing a hash digest of a stream of bytes.
+ *
+ * See the `Mx3Hasher` documentation of the mx3 crate for the stability of
+ * the output.
+ */
+typedef struct Mx3Hasher Mx3Hasher;
+
+/**
+ * Version 1 pseudo-random number generator.
+ *
+ * The field is the state of the generator and should not be modified
+ * directly.
+ */
+typedef struct Mx3V1Rng {
+  uint64_t state;
+} Mx3V1Rng;
+
+/**
+ * Version 2 pseudo-random number generator.
+ *
+ * The field is the state of the generator and should not be modified
+ * directly.
+ */
+typedef struct Mx3V2Rng {
+  uint64_t state;
+} Mx3V2Rng;
+
+/**
+ * Version 3 pseudo-random number generator.
+ *
+ * The field is the state of the generator and should not be modified
+ * directly.
+ */
+typedef struct Mx3V3Rng {
+  uint64_t state;
+} Mx3V3Rng;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Mix the bits in the integer using version 1.
+ */
+uint64_t mx3_v1_mix(uint64_t x);
+
+/**
+ * Reverse the bits mixed by [`mx3_v1_mix()`].
+ */
+uint64_t mx3_v1_unmix(uint64_t x);
+
+/**
+ * Hash the given buffer using version 1.
+ *
+ * # Safety
+ *
+ * `buffer` must be valid for reading `length` bytes.
+ */
+uint64_t mx3_v1_hash(const uint8_t *buffer, size_t length, uint64_t seed);
+
+/**
+ * Creates the version 1 generator using the given seed.
+ */
+struct Mx3V1Rng mx3_v1_rng_new(uint64_t seed);
+
+/**
+ * Creates the version 1 generator from an existing state.
+ *
+ * This is equivalent to [`mx3_v1_rng_new()`] as the version 1 generator does not
+ * modify the seed.
+ */
+struct Mx3V1Rng mx3_v1_rng_resume(uint64_t state);
+
+/**
+ * Returns the next random number from the generator.
+ *
+ * # Safety
+ *
+ * `rng` must point to a valid generator.
+ */
+uint64_t mx3_v1_rng_next(struct Mx3V1Rng *rng);
+
+/**
+ * Returns the state of the generator.
+ *
+ * The generator can be resumed by passing the state to
+ * [`mx3_v1_rng_resume()`].
+ *
+ * # Safety
+ *
+ * `rng` must point to a valid generator.
+ */
+uint64_t mx3_v1_rng_state(const struct Mx3V1Rng *rng);
+
+/**
+ * Mix the bits in the integer using version 2.
+ */
+uint64_t mx3_v2_mix(uint64_t x);
+
+/**
+ * Reverse the bits mixed by [`mx3_v2_mix()`].
+ */
+uint64_t mx3_v2_unmix(uint64_t x);
+
+/**
+ * Hash the given buffer using version 2.
+ *
+ * # Safety
+ *
+ * `buffer` must be valid for reading `length` bytes.
+ */
+uint64_t mx3_v2_hash(const uint8_t *buffer, size_t length, uint64_t seed);
+
+/**
+ * Creates the version 2 generator using the given seed.
+ */
+struct Mx3V2Rng mx3_v2_rng_new(uint64_t seed);
+
+/**
+ * Creates the version 2 generator from an existing state.
+ *
+ * This is equivalent to [`mx3_v2_rng_new()`] as the version 2 generator does not
+ * modify the seed.
+ */
+struct Mx3V2Rng mx3_v2_rng_resume(uint64_t state);
+
+/**
+ * Returns the next random number from the generator.
+ *
+ * # Safety
+ *
+ * `rng` must point to a valid generator.
+ */
+uint64_t mx3_v2_rng_next(struct Mx3V2Rng *rng);
+
+/**
+ * Returns the state of the generator.
+ *
+ * The generator can be resumed by passing the state to
+ * [`mx3_v2_rng_resume()`].
+ *
+ * # Safety
+ *
+ * `rng` must point to a valid generator.
+ */
+uint64_t mx3_v2_rng_state(const struct Mx3V2Rng *rng);
+
+/**
+ * Mix the bits in the integer using version 3.
+ */
+uint64_t mx3_v3_mix(uint64_t x);
+
+/**
+ * Reverse the bits mixed by [`mx3_v3_mix()`].
+ */
+uint64_t mx3_v3_unmix(uint64_t x);
+
+/**
+ * Hash the given buffer using version 3.
+ *
+ * # Safety
+ *
+ * `buffer` must be valid for reading `length` bytes.
+ */
+uint64_t mx3_v3_hash(const uint8_t *buffer, size_t length, uint64_t seed);
+
+/**
+ * Creates the version 3 generator using the given seed.
+ *
+ * The seed is modified before it is used in the same manner as the reference
+ * design.
+ */
+struct Mx3V3Rng mx3_v3_rng_new(uint64_t seed);
+
+/**
+ * Creates the version 3 generator from an existing state.
+ */
+struct Mx3V3Rng mx3_v3_rng_resume(uint64_t state);
+
+/**
+ * Returns the next random number from the generator.
+ *
+ * # Safety
+ *
+ * `rng` must point to a valid generator.
+ */
+uint64_t mx3_v3_rng_next(struct Mx3V3Rng *rng);
+
+/**
+ * Returns the state of the generator.
+ *
+ * The generator can be resumed by passing the state to
+ * [`mx3_v3_rng_resume()`].
+ *
+ * # Safety
+ *
+ * `rng` must point to a valid generator.
+ */
+uint64_t mx3_v3_rng_state(const struct Mx3V3Rng *rng);
+
+/**
+ * Creates a stream hasher with the given seed.
+ *
+ * The hasher must be released by [`mx3_hasher_finish()`] or
+ * [`mx3_hasher_free()`].
+ */
+struct Mx3Hasher *mx3_hasher_new(uint64_t seed);
+
+/**
+ * Adds the bytes of the buffer to the stream.
+ *
+ * # Safety
+ *
+ * `hasher` must be created by [`mx3_hasher_new()`] and not yet released.
+ * `buffer` must be valid for reading `length` bytes.
+ */
+void mx3_hasher_update(struct Mx3Hasher *hasher, const uint8_t *buffer, size_t length);
+
+/**
+ * Returns the hash digest of the stream and releases the hasher.
+ *
+ * # Safety
+ *
+ * `hasher` must be created by [`mx3_hasher_new()`] and not yet released.
+ */
+uint64_t mx3_hasher_finish(struct Mx3Hasher *hasher);
+
+/**
+ * Releases the hasher without computing the hash digest.
+ *
+ * Null is ignored.
+ *
+ * # Safety
+ *
+ * `hasher` must be null or created by [`mx3_hasher_new()`] and not yet
+ * released.
+ */
+void mx3_hasher_free(struct Mx3Hasher *hasher);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* MX3_H */
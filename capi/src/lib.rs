@@ -0,0 +1,407 @@
+//! C API for the mx3 crate.
+//!
+//! The header is `include/mx3.h`. It is generated by cbindgen into
+//! `OUT_DIR` when the crate is built, and copied to `include/mx3.h` only
+//! when the `MX3_CAPI_UPDATE_HEADER` environment variable is set. A test
+//! fails if the committed header is out of date.
+//!
+//! Pointer arguments must be valid and non-null unless stated otherwise.
+//! A buffer pointer may be null when its length is 0.
+#![deny(unsafe_op_in_unsafe_fn)]
+
+use std::hash::Hasher;
+
+use rand_core::RngCore;
+
+unsafe fn buffer_slice<'a>(buffer: *const u8, length: usize) -> &'a [u8] {
+    if length == 0 {
+        &[]
+    } else {
+        // SAFETY: the caller guarantees the buffer is valid for the length.
+        unsafe { std::slice::from_raw_parts(buffer, length) }
+    }
+}
+
+/// Version 1 pseudo-random number generator.
+///
+/// The field is the state of the generator and should not be modified
+/// directly.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Mx3V1Rng {
+    state: u64,
+}
+
+/// Mix the bits in the integer using version 1.
+#[no_mangle]
+pub extern "C" fn mx3_v1_mix(x: u64) -> u64 {
+    mx3::v1::mix(x)
+}
+
+/// Reverse the bits mixed by [`mx3_v1_mix()`].
+#[no_mangle]
+pub extern "C" fn mx3_v1_unmix(x: u64) -> u64 {
+    mx3::v1::unmix(x)
+}
+
+/// Hash the given buffer using version 1.
+///
+/// # Safety
+///
+/// `buffer` must be valid for reading `length` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn mx3_v1_hash(buffer: *const u8, length: usize, seed: u64) -> u64 {
+    // SAFETY: guaranteed by the caller.
+    let buffer = unsafe { buffer_slice(buffer, length) };
+
+    mx3::v1::hash(buffer, seed)
+}
+
+/// Creates the version 1 generator using the given seed.
+#[no_mangle]
+pub extern "C" fn mx3_v1_rng_new(seed: u64) -> Mx3V1Rng {
+    Mx3V1Rng {
+        state: mx3::v1::Mx3Rng::new(seed).state(),
+    }
+}
+
+/// Creates the version 1 generator from an existing state.
+///
+/// This is equivalent to [`mx3_v1_rng_new()`] as the version 1 generator does not
+/// modify the seed.
+#[no_mangle]
+pub extern "C" fn mx3_v1_rng_resume(state: u64) -> Mx3V1Rng {
+    Mx3V1Rng {
+        state: mx3::v1::Mx3Rng::new(state).state(),
+    }
+}
+
+/// Returns the next random number from the generator.
+///
+/// # Safety
+///
+/// `rng` must point to a valid generator.
+#[no_mangle]
+pub unsafe extern "C" fn mx3_v1_rng_next(rng: *mut Mx3V1Rng) -> u64 {
+    // SAFETY: guaranteed by the caller.
+    let rng = unsafe { &mut *rng };
+    let mut inner = mx3::v1::Mx3Rng::new(rng.state);
+    let value = inner.next_u64();
+    rng.state = inner.state();
+
+    value
+}
+
+/// Returns the state of the generator.
+///
+/// The generator can be resumed by passing the state to
+/// [`mx3_v1_rng_resume()`].
+///
+/// # Safety
+///
+/// `rng` must point to a valid generator.
+#[no_mangle]
+pub unsafe extern "C" fn mx3_v1_rng_state(rng: *const Mx3V1Rng) -> u64 {
+    // SAFETY: guaranteed by the caller.
+    unsafe { (*rng).state }
+}
+
+/// Version 2 pseudo-random number generator.
+///
+/// The field is the state of the generator and should not be modified
+/// directly.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Mx3V2Rng {
+    state: u64,
+}
+
+/// Mix the bits in the integer using version 2.
+#[no_mangle]
+pub extern "C" fn mx3_v2_mix(x: u64) -> u64 {
+    mx3::v2::mix(x)
+}
+
+/// Reverse the bits mixed by [`mx3_v2_mix()`].
+#[no_mangle]
+pub extern "C" fn mx3_v2_unmix(x: u64) -> u64 {
+    mx3::v2::unmix(x)
+}
+
+/// Hash the given buffer using version 2.
+///
+/// # Safety
+///
+/// `buffer` must be valid for reading `length` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn mx3_v2_hash(buffer: *const u8, length: usize, seed: u64) -> u64 {
+    // SAFETY: guaranteed by the caller.
+    let buffer = unsafe { buffer_slice(buffer, length) };
+
+    mx3::v2::hash(buffer, seed)
+}
+
+/// Creates the version 2 generator using the given seed.
+#[no_mangle]
+pub extern "C" fn mx3_v2_rng_new(seed: u64) -> Mx3V2Rng {
+    Mx3V2Rng {
+        state: mx3::v2::Mx3Rng::new(seed).state(),
+    }
+}
+
+/// Creates the version 2 generator from an existing state.
+///
+/// This is equivalent to [`mx3_v2_rng_new()`] as the version 2 generator does not
+/// modify the seed.
+#[no_mangle]
+pub extern "C" fn mx3_v2_rng_resume(state: u64) -> Mx3V2Rng {
+    Mx3V2Rng {
+        state: mx3::v2::Mx3Rng::new(state).state(),
+    }
+}
+
+/// Returns the next random number from the generator.
+///
+/// # Safety
+///
+/// `rng` must point to a valid generator.
+#[no_mangle]
+pub unsafe extern "C" fn mx3_v2_rng_next(rng: *mut Mx3V2Rng) -> u64 {
+    // SAFETY: guaranteed by the caller.
+    let rng = unsafe { &mut *rng };
+    let mut inner = mx3::v2::Mx3Rng::new(rng.state);
+    let value = inner.next_u64();
+    rng.state = inner.state();
+
+    value
+}
+
+/// Returns the state of the generator.
+///
+/// The generator can be resumed by passing the state to
+/// [`mx3_v2_rng_resume()`].
+///
+/// # Safety
+///
+/// `rng` must point to a valid generator.
+#[no_mangle]
+pub unsafe extern "C" fn mx3_v2_rng_state(rng: *const Mx3V2Rng) -> u64 {
+    // SAFETY: guaranteed by the caller.
+    unsafe { (*rng).state }
+}
+
+/// Version 3 pseudo-random number generator.
+///
+/// The field is the state of the generator and should not be modified
+/// directly.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Mx3V3Rng {
+    state: u64,
+}
+
+/// Mix the bits in the integer using version 3.
+#[no_mangle]
+pub extern "C" fn mx3_v3_mix(x: u64) -> u64 {
+    mx3::v3::mix(x)
+}
+
+/// Reverse the bits mixed by [`mx3_v3_mix()`].
+#[no_mangle]
+pub extern "C" fn mx3_v3_unmix(x: u64) -> u64 {
+    mx3::v3::unmix(x)
+}
+
+/// Hash the given buffer using version 3.
+///
+/// # Safety
+///
+/// `buffer` must be valid for reading `length` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn mx3_v3_hash(buffer: *const u8, length: usize, seed: u64) -> u64 {
+    // SAFETY: guaranteed by the caller.
+    let buffer = unsafe { buffer_slice(buffer, length) };
+
+    mx3::v3::hash(buffer, seed)
+}
+
+/// Creates the version 3 generator using the given seed.
+///
+/// The seed is modified before it is used in the same manner as the reference
+/// design.
+#[no_mangle]
+pub extern "C" fn mx3_v3_rng_new(seed: u64) -> Mx3V3Rng {
+    Mx3V3Rng {
+        state: mx3::v3::Mx3Rng::new(seed).state(),
+    }
+}
+
+/// Creates the version 3 generator from an existing state.
+#[no_mangle]
+pub extern "C" fn mx3_v3_rng_resume(state: u64) -> Mx3V3Rng {
+    Mx3V3Rng {
+        state: mx3::v3::Mx3Rng::resume(state).state(),
+    }
+}
+
+/// Returns the next random number from the generator.
+///
+/// # Safety
+///
+/// `rng` must point to a valid generator.
+#[no_mangle]
+pub unsafe extern "C" fn mx3_v3_rng_next(rng: *mut Mx3V3Rng) -> u64 {
+    // SAFETY: guaranteed by the caller.
+    let rng = unsafe { &mut *rng };
+    let mut inner = mx3::v3::Mx3Rng::resume(rng.state);
+    let value = inner.next_u64();
+    rng.state = inner.state();
+
+    value
+}
+
+/// Returns the state of the generator.
+///
+/// The generator can be resumed by passing the state to
+/// [`mx3_v3_rng_resume()`].
+///
+/// # Safety
+///
+/// `rng` must point to a valid generator.
+#[no_mangle]
+pub unsafe extern "C" fn mx3_v3_rng_state(rng: *const Mx3V3Rng) -> u64 {
+    // SAFETY: guaranteed by the caller.
+    unsafe { (*rng).state }
+}
+
+/// Hasher for computing a hash digest of a stream of bytes.
+///
+/// See the `Mx3Hasher` documentation of the mx3 crate for the stability of
+/// the output.
+pub struct Mx3Hasher(mx3::Mx3Hasher);
+
+/// Creates a stream hasher with the given seed.
+///
+/// The hasher must be released by [`mx3_hasher_finish()`] or
+/// [`mx3_hasher_free()`].
+#[no_mangle]
+pub extern "C" fn mx3_hasher_new(seed: u64) -> *mut Mx3Hasher {
+    Box::into_raw(Box::new(Mx3Hasher(mx3::Mx3Hasher::new(seed))))
+}
+
+/// Adds the bytes of the buffer to the stream.
+///
+/// # Safety
+///
+/// `hasher` must be created by [`mx3_hasher_new()`] and not yet released.
+/// `buffer` must be valid for reading `length` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn mx3_hasher_update(
+    hasher: *mut Mx3Hasher,
+    buffer: *const u8,
+    length: usize,
+) {
+    // SAFETY: guaranteed by the caller.
+    let (hasher, buffer) = unsafe { (&mut *hasher, buffer_slice(buffer, length)) };
+
+    hasher.0.write(buffer);
+}
+
+/// Returns the hash digest of the stream and releases the hasher.
+///
+/// # Safety
+///
+/// `hasher` must be created by [`mx3_hasher_new()`] and not yet released.
+#[no_mangle]
+pub unsafe extern "C" fn mx3_hasher_finish(hasher: *mut Mx3Hasher) -> u64 {
+    // SAFETY: guaranteed by the caller.
+    let hasher = unsafe { Box::from_raw(hasher) };
+
+    hasher.0.finish()
+}
+
+/// Releases the hasher without computing the hash digest.
+///
+/// Null is ignored.
+///
+/// # Safety
+///
+/// `hasher` must be null or created by [`mx3_hasher_new()`] and not yet
+/// released.
+#[no_mangle]
+pub unsafe extern "C" fn mx3_hasher_free(hasher: *mut Mx3Hasher) {
+    if !hasher.is_null() {
+        // SAFETY: guaranteed by the caller.
+        drop(unsafe { Box::from_raw(hasher) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_up_to_date() {
+        assert!(
+            include_str!("../include/mx3.h") == include_str!(concat!(env!("OUT_DIR"), "/mx3.h")),
+            "include/mx3.h is out of date; rebuild with MX3_CAPI_UPDATE_HEADER=1"
+        );
+    }
+
+    #[test]
+    fn test_mix() {
+        assert_eq!(mx3_v1_mix(123456789), 0x566319fa1c03230f);
+        assert_eq!(mx3_v2_mix(123456789), 0x95bd1de6327dae0a);
+        assert_eq!(mx3_v3_mix(123456789), 0x95bd1de6327dae0a);
+        assert_eq!(mx3_v3_unmix(mx3_v3_mix(123456789)), 123456789);
+    }
+
+    #[test]
+    fn test_hash() {
+        let input = b"abcdefghijklmnopqrstuvwxyz";
+
+        unsafe {
+            assert_eq!(
+                mx3_v3_hash(input.as_ptr(), input.len(), 123456789),
+                0x6c16bdf4571e7844
+            );
+            assert_eq!(
+                mx3_v3_hash(std::ptr::null(), 0, 123456789),
+                0x4e069d451e12ced8
+            );
+        }
+    }
+
+    #[test]
+    fn test_rng() {
+        let mut rng = mx3_v3_rng_new(1);
+
+        unsafe {
+            assert_eq!(mx3_v3_rng_next(&mut rng), 0xe8eb_dbc4_39df_412a);
+
+            let mut resumed = mx3_v3_rng_resume(mx3_v3_rng_state(&rng));
+            assert_eq!(mx3_v3_rng_next(&mut resumed), 0x4d47_6d54_25a1_74d9);
+
+            let mut rng = mx3_v1_rng_new(1);
+            assert_eq!(mx3_v1_rng_next(&mut rng), 0x3e1e_ad46_d36d_302b);
+            assert_eq!(mx3_v1_rng_state(&rng), 2);
+        }
+    }
+
+    #[test]
+    fn test_hasher() {
+        let input = b"abcdefghijklmnopqrstuvwxyz";
+        let mut expected = mx3::Mx3Hasher::new(123456789);
+        expected.write(input);
+
+        unsafe {
+            let hasher = mx3_hasher_new(123456789);
+            mx3_hasher_update(hasher, input.as_ptr(), 10);
+            mx3_hasher_update(hasher, input[10..].as_ptr(), input.len() - 10);
+            assert_eq!(mx3_hasher_finish(hasher), expected.finish());
+
+            mx3_hasher_free(mx3_hasher_new(1));
+            mx3_hasher_free(std::ptr::null_mut());
+        }
+    }
+}
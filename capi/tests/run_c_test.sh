@@ -0,0 +1,21 @@
+#!/bin/sh
+# Builds the C API library and the C test program, then runs the program.
+#
+# The C compiler can be changed with the CC environment variable.
+
+set -e
+
+CAPI_DIR="$(cd "$(dirname "$0")/.." && pwd)"
+TARGET_DIR="$(cd "$CAPI_DIR/.." && pwd)/target/debug"
+OUTPUT_DIR="$TARGET_DIR/capi-test"
+
+cargo build --manifest-path "$CAPI_DIR/Cargo.toml"
+
+mkdir -p "$OUTPUT_DIR"
+"${CC:-cc}" -std=c99 -Wall -Wextra -Werror \
+    -I "$CAPI_DIR/include" \
+    "$CAPI_DIR/tests/test.c" \
+    -L "$TARGET_DIR" -lmx3_capi \
+    -o "$OUTPUT_DIR/test"
+
+LD_LIBRARY_PATH="$TARGET_DIR" DYLD_LIBRARY_PATH="$TARGET_DIR" "$OUTPUT_DIR/test"
@@ -0,0 +1,66 @@
+// Test program for the C API.
+//
+// Build and run it using `run_c_test.sh`.
+
+#include <inttypes.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "mx3.h"
+
+static int failures = 0;
+
+static void check(const char *name, uint64_t value, uint64_t expected) {
+    if (value != expected) {
+        printf("FAIL %s: %" PRIx64 " != %" PRIx64 "\n", name, value, expected);
+        failures++;
+    } else {
+        printf("ok %s\n", name);
+    }
+}
+
+int main(void) {
+    const uint8_t input[] = "abcdefghijklmnopqrstuvwxyz";
+    const size_t length = 26;
+
+    check("v1 mix", mx3_v1_mix(123456789), 0x566319fa1c03230f);
+    check("v2 mix", mx3_v2_mix(123456789), 0x95bd1de6327dae0a);
+    check("v3 mix", mx3_v3_mix(123456789), 0x95bd1de6327dae0a);
+    check("v3 unmix", mx3_v3_unmix(0x95bd1de6327dae0a), 123456789);
+
+    check("v1 hash", mx3_v1_hash(input, length, 123456789), 0x1e29585a2a634374);
+    check("v2 hash", mx3_v2_hash(input, length, 123456789), 0xf1673daba637e36);
+    check("v3 hash", mx3_v3_hash(input, length, 123456789), 0x6c16bdf4571e7844);
+    check("v3 hash empty", mx3_v3_hash(NULL, 0, 123456789), 0x4e069d451e12ced8);
+
+    Mx3V1Rng rng1 = mx3_v1_rng_new(1);
+    check("v1 rng", mx3_v1_rng_next(&rng1), 0x3e1ead46d36d302b);
+    check("v1 rng", mx3_v1_rng_next(&rng1), 0xaaf908c732d70fa6);
+
+    Mx3V2Rng rng2 = mx3_v2_rng_new(1);
+    check("v2 rng", mx3_v2_rng_next(&rng2), 0x071894de00d9981f);
+
+    Mx3V3Rng rng3 = mx3_v3_rng_new(1);
+    check("v3 rng", mx3_v3_rng_next(&rng3), 0xe8ebdbc439df412a);
+
+    Mx3V3Rng resumed = mx3_v3_rng_resume(mx3_v3_rng_state(&rng3));
+    check("v3 rng resume", mx3_v3_rng_next(&resumed), 0x4d476d5425a174d9);
+
+    Mx3Hasher *hasher = mx3_hasher_new(123456789);
+    mx3_hasher_update(hasher, NULL, 0);
+    check("hasher empty", mx3_hasher_finish(hasher), 0x95bd1de6327dae0a);
+
+    Mx3Hasher *one_shot = mx3_hasher_new(123456789);
+    mx3_hasher_update(one_shot, input, length);
+
+    Mx3Hasher *streaming = mx3_hasher_new(123456789);
+    mx3_hasher_update(streaming, input, 10);
+    mx3_hasher_update(streaming, input + 10, length - 10);
+
+    check("hasher streaming", mx3_hasher_finish(streaming), mx3_hasher_finish(one_shot));
+
+    mx3_hasher_free(mx3_hasher_new(1));
+    mx3_hasher_free(NULL);
+
+    return failures == 0 ? 0 : 1;
+}
@@ -1,20 +1,155 @@
-use std::io::Write;
+// Example program that writes the generator output to stdout for use with
+// external test batteries such as PractRand and TestU01.
+//
+// Usage: rng [OPTIONS]
+//
+// Options:
+//   --version VERSION   Generator version: v1, v2, or v3. Default: v3.
+//   --seed SEED         Seed in decimal or hex with a 0x prefix. Default: 1.
+//   --bits BITS         Word size: 32 or 64. Default: 64.
+//   --limit BYTES       Stop after writing the number of bytes. Default: no limit.
+//   --streams COUNT     Interleave words from generators with consecutive
+//                       seeds starting from SEED. Default: 1.
+//   --reverse-bits      Reverse the bits of each word.
+//
+// Words are written in little-endian byte order. 32-bit words are the
+// lower bits of the 64-bit output.
+//
+// Example: cargo run --release --example rng -- --bits 32 | RNG_test stdin32
 
-use mx3::v3::Mx3Rng;
+use std::io::{BufWriter, ErrorKind, Write};
+
+use mx3::{Version, VersionedRng};
 use rand::RngCore;
 
-fn main() -> Result<(), std::io::Error> {
-    let mut rng = Mx3Rng::new(1);
-    let mut stdout = std::io::stdout();
+struct Options {
+    version: Version,
+    seed: u64,
+    bits: u32,
+    limit: Option<u64>,
+    streams: u64,
+    reverse_bits: bool,
+}
+
+fn parse_integer(text: &str) -> Result<u64, String> {
+    let result = match text.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => text.parse(),
+    };
+
+    result.map_err(|error| format!("invalid integer {:?}: {}", text, error))
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        version: Version::V3,
+        seed: 1,
+        bits: 64,
+        limit: None,
+        streams: 1,
+        reverse_bits: false,
+    };
+
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        if arg == "--reverse-bits" {
+            options.reverse_bits = true;
+            continue;
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {}", arg))?;
+
+        match arg.as_str() {
+            "--version" => {
+                options.version = value
+                    .parse()
+                    .map_err(|_| format!("invalid version {:?}", value))?
+            }
+            "--seed" => options.seed = parse_integer(&value)?,
+            "--bits" => {
+                options.bits = match value.as_str() {
+                    "32" => 32,
+                    "64" => 64,
+                    _ => return Err(format!("invalid word size {:?}", value)),
+                }
+            }
+            "--limit" => options.limit = Some(parse_integer(&value)?),
+            "--streams" => {
+                options.streams = parse_integer(&value)?;
+
+                if options.streams == 0 {
+                    return Err("streams must be at least 1".to_string());
+                }
+            }
+            _ => return Err(format!("unknown option {:?}", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn write_words(options: &Options, output: &mut impl Write) -> Result<(), std::io::Error> {
+    let mut rngs: Vec<VersionedRng> = (0..options.streams)
+        .map(|index| options.version.rng(options.seed.wrapping_add(index)))
+        .collect();
+    let mut remain = options.limit.unwrap_or(u64::MAX);
 
     loop {
-        let buf = rng.next_u64().to_le_bytes();
-        let size = stdout.write(&buf)?;
+        for rng in rngs.iter_mut() {
+            let mut buf = [0u8; 8];
+
+            let word = if options.bits == 32 {
+                let mut word = rng.next_u32();
+                if options.reverse_bits {
+                    word = word.reverse_bits();
+                }
+                buf[0..4].copy_from_slice(&word.to_le_bytes());
+                &buf[0..4]
+            } else {
+                let mut word = rng.next_u64();
+                if options.reverse_bits {
+                    word = word.reverse_bits();
+                }
+                buf.copy_from_slice(&word.to_le_bytes());
+                &buf[..]
+            };
+
+            let amount = remain.min(word.len() as u64) as usize;
+            output.write_all(&word[0..amount])?;
+
+            if options.limit.is_some() {
+                remain -= amount as u64;
 
-        if size == 0 {
-            break;
+                if remain == 0 {
+                    return output.flush();
+                }
+            }
         }
     }
+}
+
+fn main() {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            std::process::exit(2);
+        }
+    };
 
-    Ok(())
+    let stdout = std::io::stdout();
+    let mut output = BufWriter::new(stdout.lock());
+
+    match write_words(&options, &mut output) {
+        Ok(()) => {}
+        // The test battery closing the pipe is the normal way to stop.
+        Err(error) if error.kind() == ErrorKind::BrokenPipe => {}
+        Err(error) => {
+            eprintln!("error: {}", error);
+            std::process::exit(1);
+        }
+    }
 }
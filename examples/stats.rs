@@ -0,0 +1,21 @@
+// Example program that runs the statistical quality tests on each version.
+//
+// The optional argument is the number of samples which scales the amount
+// of work. The default is 10000 and the minimum is 100.
+
+use mx3::{stats, Version};
+
+fn main() {
+    let samples = match std::env::args().nth(1) {
+        Some(text) => text.parse().expect("samples must be an integer"),
+        None => 10000,
+    };
+
+    for version in [Version::V1, Version::V2, Version::V3] {
+        println!("{}", version);
+
+        for result in stats::run_all(version, samples) {
+            println!("  {}", result);
+        }
+    }
+}
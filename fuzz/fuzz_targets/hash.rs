@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mx3::{v1, v2, v3, Version};
+
+fuzz_target!(|input: (u64, &[u8])| {
+    let (seed, buffer) = input;
+
+    assert_eq!(Version::V1.hash(buffer, seed), v1::hash(buffer, seed));
+    assert_eq!(Version::V2.hash(buffer, seed), v2::hash(buffer, seed));
+    assert_eq!(Version::V3.hash(buffer, seed), v3::hash(buffer, seed));
+});
@@ -0,0 +1,31 @@
+#![no_main]
+
+use std::hash::Hasher;
+
+use libfuzzer_sys::fuzz_target;
+use mx3::Mx3Hasher;
+
+fuzz_target!(|input: (u64, Vec<u16>, &[u8])| {
+    let (seed, mut points, buffer) = input;
+
+    let mut one_shot = Mx3Hasher::new(seed);
+    one_shot.write(buffer);
+
+    for point in points.iter_mut() {
+        *point = (*point as usize % (buffer.len() + 1)) as u16;
+    }
+    points.sort_unstable();
+
+    let mut streaming = Mx3Hasher::new(seed);
+    let mut start = 0;
+
+    for point in points {
+        let point = point as usize;
+        streaming.write(&buffer[start..point]);
+        start = point;
+    }
+
+    streaming.write(&buffer[start..]);
+
+    assert_eq!(streaming.finish(), one_shot.finish());
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mx3::Version;
+
+fuzz_target!(|x: u64| {
+    for version in [Version::V1, Version::V2, Version::V3] {
+        assert_eq!(version.unmix(version.mix(x)), x);
+        assert_eq!(version.mix(version.unmix(x)), x);
+    }
+});
@@ -0,0 +1,30 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mx3::{v1, v2, v3};
+use rand_core::RngCore;
+
+fuzz_target!(|input: (u64, u16)| {
+    let (seed, steps) = input;
+
+    let mut rng1 = v1::Mx3Rng::new(seed);
+    let mut rng2 = v2::Mx3Rng::new(seed);
+    let mut rng3 = v3::Mx3Rng::new(seed);
+
+    let mut skipped1 = rng1.clone();
+    let mut skipped2 = rng2.clone();
+    let mut skipped3 = rng3.clone();
+
+    for _ in 0..steps {
+        rng1.next_u64();
+        rng2.next_u64();
+        rng3.next_u64();
+    }
+
+    assert_eq!(skipped1.iter_u64().nth(steps as usize), Some(rng1.next_u64()));
+    assert_eq!(skipped2.iter_u64().nth(steps as usize), Some(rng2.next_u64()));
+    assert_eq!(skipped3.iter_u64().nth(steps as usize), Some(rng3.next_u64()));
+    assert_eq!(skipped1.next_u64(), rng1.next_u64());
+    assert_eq!(skipped2.next_u64(), rng2.next_u64());
+    assert_eq!(skipped3.next_u64(), rng3.next_u64());
+});
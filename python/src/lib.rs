@@ -0,0 +1,363 @@
+//! Python bindings for the mx3 crate.
+//!
+//! The functions accept a `version` keyword argument selecting the version
+//! of the algorithm which defaults to 3. Integer arguments may be NumPy
+//! `uint64` arrays in which case the function is applied to each element.
+#![deny(unsafe_op_in_unsafe_fn)]
+
+use std::ffi::{c_void, CStr};
+
+use mx3::{v1, v2, v3, Version, VersionedRng};
+use numpy::{IntoPyArray, PyReadonlyArrayDyn};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyByteArray, PyBytes, PyCapsule, PyDict, PyInt};
+use rand_core::RngCore;
+
+const CAPSULE_NAME: &CStr = c"BitGenerator";
+
+fn parse_version(version: u8) -> PyResult<Version> {
+    match version {
+        1 => Ok(Version::V1),
+        2 => Ok(Version::V2),
+        3 => Ok(Version::V3),
+        _ => Err(PyValueError::new_err("version must be 1, 2, or 3")),
+    }
+}
+
+fn version_number(version: Version) -> u8 {
+    match version {
+        Version::V1 => 1,
+        Version::V2 => 2,
+        Version::V3 => 3,
+    }
+}
+
+fn map_integers<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+    function: impl Fn(u64) -> u64 + Send + Sync,
+) -> PyResult<Bound<'py, PyAny>> {
+    if x.is_instance_of::<PyInt>() {
+        Ok(function(x.extract()?).into_pyobject(py)?.into_any())
+    } else {
+        let array: PyReadonlyArrayDyn<u64> = x.extract()?;
+        let array = array.as_array();
+        let output = py.detach(|| array.mapv(function));
+
+        Ok(output.into_pyarray(py).into_any())
+    }
+}
+
+/// Mix the bits in the integer or each integer in the `uint64` array.
+#[pyfunction]
+#[pyo3(signature = (x, version = 3))]
+fn mix<'py>(py: Python<'py>, x: &Bound<'py, PyAny>, version: u8) -> PyResult<Bound<'py, PyAny>> {
+    let version = parse_version(version)?;
+
+    map_integers(py, x, move |x| version.mix(x))
+}
+
+/// Reverse the bits mixed by `mix()`.
+#[pyfunction]
+#[pyo3(signature = (x, version = 3))]
+fn unmix<'py>(py: Python<'py>, x: &Bound<'py, PyAny>, version: u8) -> PyResult<Bound<'py, PyAny>> {
+    let version = parse_version(version)?;
+
+    map_integers(py, x, move |x| version.unmix(x))
+}
+
+/// Hash the bytes, or each bytes object in the iterable into a `uint64` array.
+///
+/// NumPy arrays of the `S` data type remove trailing null bytes from their
+/// elements, so use arrays of the `object` data type if the trailing bytes
+/// are significant.
+///
+/// This hasher is *not* cryptographically secure.
+#[pyfunction]
+#[pyo3(signature = (data, seed, version = 3))]
+fn hash<'py>(
+    py: Python<'py>,
+    data: &Bound<'py, PyAny>,
+    seed: u64,
+    version: u8,
+) -> PyResult<Bound<'py, PyAny>> {
+    let version = parse_version(version)?;
+
+    if let Ok(bytes) = data.cast::<PyBytes>() {
+        let digest = version.hash(bytes.as_bytes(), seed);
+        return Ok(digest.into_pyobject(py)?.into_any());
+    }
+
+    if let Ok(bytes) = data.cast::<PyByteArray>() {
+        let digest = version.hash(&bytes.to_vec(), seed);
+        return Ok(digest.into_pyobject(py)?.into_any());
+    }
+
+    let mut digests = Vec::new();
+
+    for item in data.try_iter()? {
+        let item = item?;
+        let bytes = item
+            .cast::<PyBytes>()
+            .map_err(|_| PyTypeError::new_err("expected bytes or an iterable of bytes"))?;
+
+        digests.push(version.hash(bytes.as_bytes(), seed));
+    }
+
+    Ok(digests.into_pyarray(py).into_any())
+}
+
+/// The `bitgen_t` struct of the NumPy C API.
+#[repr(C)]
+struct BitGen {
+    state: *mut c_void,
+    next_uint64: unsafe extern "C" fn(*mut c_void) -> u64,
+    next_uint32: unsafe extern "C" fn(*mut c_void) -> u32,
+    next_double: unsafe extern "C" fn(*mut c_void) -> f64,
+    next_raw: unsafe extern "C" fn(*mut c_void) -> u64,
+}
+
+/// Contents of the capsule. The state of the `BitGen` points to the `rng`.
+#[repr(C)]
+struct BitGenContents {
+    bitgen: BitGen,
+    rng: VersionedRng,
+}
+
+unsafe extern "C" fn next_uint64(state: *mut c_void) -> u64 {
+    // SAFETY: NumPy passes the state of the BitGen which points to the rng.
+    let rng = unsafe { &mut *(state as *mut VersionedRng) };
+    rng.next_u64()
+}
+
+unsafe extern "C" fn next_uint32(state: *mut c_void) -> u32 {
+    // SAFETY: NumPy passes the state of the BitGen which points to the rng.
+    let rng = unsafe { &mut *(state as *mut VersionedRng) };
+    rng.next_u32()
+}
+
+unsafe extern "C" fn next_double(state: *mut c_void) -> f64 {
+    // SAFETY: NumPy passes the state of the BitGen which points to the rng.
+    let rng = unsafe { &mut *(state as *mut VersionedRng) };
+    (rng.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+}
+
+unsafe extern "C" fn destroy_capsule(capsule: *mut pyo3::ffi::PyObject) {
+    // SAFETY: the capsule was created by `new_capsule()` with this name.
+    unsafe {
+        let pointer = pyo3::ffi::PyCapsule_GetPointer(capsule, CAPSULE_NAME.as_ptr());
+        drop(Box::from_raw(pointer as *mut BitGenContents));
+    }
+}
+
+fn new_capsule(py: Python<'_>, rng: VersionedRng) -> PyResult<Py<PyCapsule>> {
+    let contents = Box::into_raw(Box::new(BitGenContents {
+        bitgen: BitGen {
+            state: std::ptr::null_mut(),
+            next_uint64,
+            next_uint32,
+            next_double,
+            next_raw: next_uint64,
+        },
+        rng,
+    }));
+
+    // SAFETY: the contents are leaked until the capsule destructor is called.
+    unsafe {
+        (*contents).bitgen.state = &mut (*contents).rng as *mut VersionedRng as *mut c_void;
+
+        let capsule = pyo3::ffi::PyCapsule_New(
+            contents as *mut c_void,
+            CAPSULE_NAME.as_ptr(),
+            Some(destroy_capsule),
+        );
+
+        if capsule.is_null() {
+            drop(Box::from_raw(contents));
+        }
+
+        Ok(Bound::from_owned_ptr_or_err(py, capsule)?
+            .cast_into::<PyCapsule>()?
+            .unbind())
+    }
+}
+
+fn resume(version: Version, state: u64) -> VersionedRng {
+    match version {
+        Version::V1 => v1::Mx3Rng::new(state).into(),
+        Version::V2 => v2::Mx3Rng::new(state).into(),
+        Version::V3 => v3::Mx3Rng::resume(state).into(),
+    }
+}
+
+/// Pseudo-random number generator with 64-bits of state and cycle of 2^64.
+///
+/// The object can be passed to `numpy.random.Generator` like the bit
+/// generators of NumPy. If no seed is given, a seed is taken from
+/// `os.urandom()`.
+///
+/// This RNG is *not* cryptographically secure.
+#[pyclass(module = "mx3")]
+struct Mx3Rng {
+    capsule: Py<PyCapsule>,
+    lock: Py<PyAny>,
+}
+
+impl Mx3Rng {
+    fn from_rng(py: Python<'_>, rng: VersionedRng) -> PyResult<Self> {
+        Ok(Self {
+            capsule: new_capsule(py, rng)?,
+            lock: py.import("threading")?.call_method0("Lock")?.unbind(),
+        })
+    }
+
+    /// Runs the function with the generator while holding the lock.
+    ///
+    /// NumPy holds the same lock, with the GIL released, while it calls the
+    /// functions of the capsule, so every access to the state from Python
+    /// must hold it as well.
+    fn with_rng<R>(
+        &self,
+        py: Python<'_>,
+        function: impl FnOnce(&mut VersionedRng) -> R,
+    ) -> PyResult<R> {
+        let _guard = LockGuard::acquire(self.lock.bind(py))?;
+        let capsule = self.capsule.bind(py);
+
+        // SAFETY: the capsule is owned by this object and contains the
+        // BitGenContents. Access is serialized by the lock, which is held
+        // until the guard is dropped.
+        let rng = unsafe {
+            let pointer = pyo3::ffi::PyCapsule_GetPointer(capsule.as_ptr(), CAPSULE_NAME.as_ptr());
+            &mut (*(pointer as *mut BitGenContents)).rng
+        };
+
+        Ok(function(rng))
+    }
+}
+
+/// Holds a `threading.Lock` until dropped.
+struct LockGuard<'a, 'py> {
+    lock: &'a Bound<'py, PyAny>,
+}
+
+impl<'a, 'py> LockGuard<'a, 'py> {
+    fn acquire(lock: &'a Bound<'py, PyAny>) -> PyResult<Self> {
+        lock.call_method0("acquire")?;
+
+        Ok(Self { lock })
+    }
+}
+
+impl Drop for LockGuard<'_, '_> {
+    fn drop(&mut self) {
+        if let Err(error) = self.lock.call_method0("release") {
+            error.write_unraisable(self.lock.py(), Some(self.lock));
+        }
+    }
+}
+
+#[pymethods]
+impl Mx3Rng {
+    #[new]
+    #[pyo3(signature = (seed = None, version = 3))]
+    fn new(py: Python<'_>, seed: Option<u64>, version: u8) -> PyResult<Self> {
+        let version = parse_version(version)?;
+        let seed = match seed {
+            Some(seed) => seed,
+            None => {
+                let bytes: [u8; 8] = py.import("os")?.call_method1("urandom", (8,))?.extract()?;
+                u64::from_le_bytes(bytes)
+            }
+        };
+
+        Self::from_rng(py, version.rng(seed))
+    }
+
+    /// Creates the generator from an existing state.
+    #[staticmethod]
+    #[pyo3(signature = (state, version = 3))]
+    fn resume(py: Python<'_>, state: u64, version: u8) -> PyResult<Self> {
+        Self::from_rng(py, resume(parse_version(version)?, state))
+    }
+
+    /// Returns the next random 64-bit integer.
+    fn next_u64(&self, py: Python<'_>) -> PyResult<u64> {
+        self.with_rng(py, |rng| rng.next_u64())
+    }
+
+    /// Returns a random integer, or a `uint64` array of the given size.
+    #[pyo3(signature = (size = None))]
+    fn random_raw<'py>(&self, py: Python<'py>, size: Option<usize>) -> PyResult<Bound<'py, PyAny>> {
+        match size {
+            None => {
+                let value = self.with_rng(py, |rng| rng.next_u64())?;
+                Ok(value.into_pyobject(py)?.into_any())
+            }
+            Some(size) => {
+                let values: Vec<u64> =
+                    self.with_rng(py, |rng| (0..size).map(|_| rng.next_u64()).collect())?;
+                Ok(values.into_pyarray(py).into_any())
+            }
+        }
+    }
+
+    /// The state of the generator as a dict.
+    #[getter]
+    fn get_state<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let (version, counter) = self.with_rng(py, |rng| (rng.version(), rng.state()))?;
+        let inner = PyDict::new(py);
+        inner.set_item("version", version_number(version))?;
+        inner.set_item("counter", counter)?;
+
+        let state = PyDict::new(py);
+        state.set_item("bit_generator", "Mx3Rng")?;
+        state.set_item("state", inner)?;
+
+        Ok(state)
+    }
+
+    #[setter]
+    fn set_state(&self, py: Python<'_>, state: &Bound<'_, PyDict>) -> PyResult<()> {
+        let name: String = state
+            .get_item("bit_generator")?
+            .ok_or_else(|| PyValueError::new_err("state is missing bit_generator"))?
+            .extract()?;
+
+        if name != "Mx3Rng" {
+            return Err(PyValueError::new_err("state is not of Mx3Rng"));
+        }
+
+        let inner = state
+            .get_item("state")?
+            .ok_or_else(|| PyValueError::new_err("state is missing state"))?;
+        let version = parse_version(inner.get_item("version")?.extract()?)?;
+        let counter: u64 = inner.get_item("counter")?.extract()?;
+
+        self.with_rng(py, |rng| *rng = resume(version, counter))
+    }
+
+    /// The capsule containing the `bitgen_t` struct used by NumPy.
+    #[getter]
+    fn capsule(&self, py: Python<'_>) -> Py<PyCapsule> {
+        self.capsule.clone_ref(py)
+    }
+
+    /// The lock used by NumPy for thread safety.
+    #[getter]
+    fn lock(&self, py: Python<'_>) -> Py<PyAny> {
+        self.lock.clone_ref(py)
+    }
+}
+
+/// Python bindings for the mx3 algorithm.
+#[pymodule(name = "mx3")]
+fn mx3_python(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(mix, module)?)?;
+    module.add_function(wrap_pyfunction!(unmix, module)?)?;
+    module.add_function(wrap_pyfunction!(hash, module)?)?;
+    module.add_class::<Mx3Rng>()?;
+
+    Ok(())
+}
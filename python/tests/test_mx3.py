@@ -0,0 +1,124 @@
+import threading
+
+import numpy
+import pytest
+
+import mx3
+
+
+def test_mix():
+    assert mx3.mix(123456789) == 0x95BD1DE6327DAE0A
+    assert mx3.mix(123456789, version=1) == 0x566319FA1C03230F
+    assert mx3.unmix(0x95BD1DE6327DAE0A) == 123456789
+
+
+def test_mix_array():
+    array = numpy.array([[0, 1], [123456789, 2**64 - 1]], dtype=numpy.uint64)
+    output = mx3.mix(array)
+
+    assert output.dtype == numpy.uint64
+    assert output.shape == (2, 2)
+    assert output[1, 0] == 0x95BD1DE6327DAE0A
+    assert numpy.array_equal(mx3.unmix(output), array)
+
+
+def test_invalid_version():
+    with pytest.raises(ValueError):
+        mx3.mix(1, version=4)
+
+
+def test_hash():
+    input = b"abcdefghijklmnopqrstuvwxyz"
+
+    assert mx3.hash(input, 123456789) == 0x6C16BDF4571E7844
+    assert mx3.hash(bytearray(input), 123456789) == 0x6C16BDF4571E7844
+    assert mx3.hash(input, 123456789, version=2) == 0x0F1673DABA637E36
+    assert mx3.hash(b"", 123456789) == 0x4E069D451E12CED8
+
+
+def test_hash_array():
+    inputs = numpy.array([b"", b"abcdefghijklmnopqrstuvwxyz"], dtype=object)
+    output = mx3.hash(inputs, 123456789)
+
+    assert output.dtype == numpy.uint64
+    assert list(output) == [0x4E069D451E12CED8, 0x6C16BDF4571E7844]
+    assert list(mx3.hash([b"", b"abc"], 1)) == [mx3.hash(b"", 1), mx3.hash(b"abc", 1)]
+
+    with pytest.raises(TypeError):
+        mx3.hash(["abc"], 1)
+
+
+def test_rng():
+    rng = mx3.Mx3Rng(1)
+
+    assert rng.next_u64() == 0xE8EBDBC439DF412A
+    assert rng.random_raw() == 0x4D476D5425A174D9
+    assert rng.random_raw(3).shape == (3,)
+
+    rng = mx3.Mx3Rng(1, version=1)
+
+    assert rng.next_u64() == 0x3E1EAD46D36D302B
+
+
+def test_rng_state():
+    rng = mx3.Mx3Rng(1)
+    rng.next_u64()
+    state = rng.state
+
+    assert state["bit_generator"] == "Mx3Rng"
+    assert state["state"]["version"] == 3
+
+    expected = rng.next_u64()
+    rng.state = state
+
+    assert rng.next_u64() == expected
+    assert mx3.Mx3Rng.resume(state["state"]["counter"]).next_u64() == expected
+
+    with pytest.raises(ValueError):
+        rng.state = {"bit_generator": "PCG64", "state": {}}
+
+
+def test_numpy_generator():
+    rng = mx3.Mx3Rng(1)
+    generator = numpy.random.Generator(rng)
+
+    assert generator.bit_generator is rng
+
+    values = generator.random(1000)
+
+    assert values.min() >= 0.0
+    assert values.max() < 1.0
+    assert 0.4 < values.mean() < 0.6
+
+    generator.normal(size=10)
+    generator.shuffle(numpy.arange(10))
+
+
+def test_numpy_generator_reproducible():
+    first = numpy.random.Generator(mx3.Mx3Rng(123)).random(10)
+    second = numpy.random.Generator(mx3.Mx3Rng(123)).random(10)
+
+    assert numpy.array_equal(first, second)
+
+
+def test_rng_threads():
+    rng = mx3.Mx3Rng(1)
+    generator = numpy.random.Generator(rng)
+    start = rng.state["state"]["counter"]
+
+    def draw():
+        for _ in range(100):
+            generator.random(1000)
+            rng.random_raw(1000)
+            rng.next_u64()
+
+    threads = [threading.Thread(target=draw) for _ in range(4)]
+
+    for thread in threads:
+        thread.start()
+
+    for thread in threads:
+        thread.join()
+
+    # Each value takes one output, so no updates of the state were lost.
+    assert rng.state["state"]["counter"] == (start + 4 * 100 * 2001) % 2**64
@@ -0,0 +1,405 @@
+//! Content-defined chunking for deduplication.
+//!
+//! [`Chunker`] splits data into chunks whose boundaries depend on the
+//! content, so inserting or removing bytes only changes the chunks near the
+//! edit. The boundaries are found using the FastCDC algorithm described in
+//! "FastCDC: a Fast and Efficient Content-Defined Chunking Approach for Data
+//! Deduplication" by Xia et al. with normalized chunking. Each chunk is
+//! identified by its [`crate::v3::hash()`].
+//!
+//! The gear table of the rolling hash is generated by [`Mx3Rng`], so the
+//! boundaries are [stable](crate#stability).
+//!
+//! ```rust
+//! use mx3::chunking::Chunker;
+//!
+//! let data: Vec<u8> = (0..100_000u32).map(|i| mx3::v3::mix(i as u64 / 3) as u8).collect();
+//! let chunker = Chunker::new(2048, 8192, 32768);
+//!
+//! let mut offset = 0;
+//!
+//! for (chunk, chunk_data) in chunker.chunks(&data) {
+//!     assert_eq!(chunk.offset, offset);
+//!     assert_eq!(chunk.hash, mx3::v3::hash(chunk_data, 0));
+//!     offset += chunk.len as u64;
+//! }
+//!
+//! assert_eq!(offset, data.len() as u64);
+//! ```
+//!
+//! With the `std` feature, [`Chunker::read_chunks()`] chunks a reader.
+use rand_core::RngCore;
+
+use crate::v3::{hash, Mx3Rng};
+
+const GEAR_SEED: u64 = 0x6765_6172;
+
+/// Finds content-defined chunk boundaries.
+#[derive(Clone)]
+pub struct Chunker {
+    gear: [u64; 256],
+    min_size: usize,
+    avg_size: usize,
+    max_size: usize,
+    mask_small: u64,
+    mask_large: u64,
+}
+
+impl Chunker {
+    /// Creates a chunker with the given minimum, average, and maximum chunk
+    /// sizes in bytes.
+    ///
+    /// Only the last chunk of the data may be smaller than the minimum size.
+    /// The average size is rounded to a power of 2 when choosing the
+    /// boundaries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sizes are not `0 < min_size <= avg_size <= max_size`
+    /// or if `avg_size` is less than 4.
+    pub fn new(min_size: usize, avg_size: usize, max_size: usize) -> Self {
+        assert!(min_size > 0, "min_size must not be 0");
+        assert!(avg_size >= 4, "avg_size must be at least 4");
+        assert!(
+            min_size <= avg_size && avg_size <= max_size,
+            "sizes must be min_size <= avg_size <= max_size"
+        );
+
+        let mut rng = Mx3Rng::new(GEAR_SEED);
+        let mut gear = [0u64; 256];
+
+        for value in gear.iter_mut() {
+            *value = rng.next_u64();
+        }
+
+        let bits = (libm::round(libm::log2(avg_size as f64)) as u32).min(62);
+
+        Self {
+            gear,
+            min_size,
+            avg_size,
+            max_size,
+            mask_small: u64::MAX << (64 - (bits + 1)),
+            mask_large: u64::MAX << (64 - (bits - 1)),
+        }
+    }
+
+    /// Returns the minimum chunk size.
+    pub fn min_size(&self) -> usize {
+        self.min_size
+    }
+
+    /// Returns the average chunk size.
+    pub fn avg_size(&self) -> usize {
+        self.avg_size
+    }
+
+    /// Returns the maximum chunk size.
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Returns the length of the first chunk of the data.
+    ///
+    /// If the data may continue, the boundary is only final when the
+    /// data is at least [`Self::max_size()`] bytes long.
+    pub fn cut(&self, data: &[u8]) -> usize {
+        if data.len() <= self.min_size {
+            return data.len();
+        }
+
+        let end = data.len().min(self.max_size);
+        let normal = end.min(self.avg_size);
+        let mut fingerprint = 0u64;
+
+        for (index, &byte) in data.iter().enumerate().take(normal).skip(self.min_size) {
+            fingerprint = (fingerprint << 1).wrapping_add(self.gear[byte as usize]);
+
+            if fingerprint & self.mask_small == 0 {
+                return index + 1;
+            }
+        }
+
+        for (index, &byte) in data.iter().enumerate().take(end).skip(normal) {
+            fingerprint = (fingerprint << 1).wrapping_add(self.gear[byte as usize]);
+
+            if fingerprint & self.mask_large == 0 {
+                return index + 1;
+            }
+        }
+
+        end
+    }
+
+    /// Returns an iterator over the chunks of the data and their bytes.
+    pub fn chunks<'a>(&'a self, data: &'a [u8]) -> Chunks<'a> {
+        Chunks {
+            chunker: self,
+            remain: data,
+            offset: 0,
+        }
+    }
+
+    /// Returns an iterator over the chunks read from the reader and their
+    /// bytes.
+    ///
+    /// The chunks are the same as returned by [`Self::chunks()`] on the
+    /// whole content of the reader.
+    ///
+    /// Up to [`Self::max_size()`] bytes are buffered in memory, so a chunker
+    /// with a very large maximum size reads the whole content of the reader
+    /// into memory.
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[cfg(feature = "std")]
+    pub fn read_chunks<R: std::io::Read>(&self, reader: R) -> ReadChunks<'_, R> {
+        ReadChunks {
+            chunker: self,
+            reader,
+            buf: std::vec::Vec::new(),
+            offset: 0,
+            eof: false,
+        }
+    }
+}
+
+impl core::fmt::Debug for Chunker {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Chunker")
+            .field("min_size", &self.min_size)
+            .field("avg_size", &self.avg_size)
+            .field("max_size", &self.max_size)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Location and identifier of a chunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Chunk {
+    /// Position of the first byte of the chunk in the data.
+    pub offset: u64,
+    /// Length of the chunk in bytes.
+    pub len: usize,
+    /// The [`crate::v3::hash()`] of the chunk with a seed of 0.
+    pub hash: u64,
+}
+
+/// Iterator returned by [`Chunker::chunks()`].
+#[derive(Clone, Debug)]
+pub struct Chunks<'a> {
+    chunker: &'a Chunker,
+    remain: &'a [u8],
+    offset: u64,
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = (Chunk, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remain.is_empty() {
+            return None;
+        }
+
+        let len = self.chunker.cut(self.remain);
+        let (data, remain) = self.remain.split_at(len);
+        let chunk = Chunk {
+            offset: self.offset,
+            len,
+            hash: hash(data, 0),
+        };
+
+        self.remain = remain;
+        self.offset += len as u64;
+
+        Some((chunk, data))
+    }
+}
+
+/// Iterator returned by [`Chunker::read_chunks()`].
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct ReadChunks<'a, R> {
+    chunker: &'a Chunker,
+    reader: R,
+    buf: std::vec::Vec<u8>,
+    offset: u64,
+    eof: bool,
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> ReadChunks<'_, R> {
+    fn fill_buf(&mut self) -> std::io::Result<()> {
+        use std::io::Read;
+
+        if self.eof || self.buf.len() >= self.chunker.max_size {
+            return Ok(());
+        }
+
+        let limit = (self.chunker.max_size - self.buf.len()) as u64;
+        let amount = (&mut self.reader).take(limit).read_to_end(&mut self.buf)?;
+
+        if (amount as u64) < limit {
+            self.eof = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> Iterator for ReadChunks<'_, R> {
+    type Item = std::io::Result<(Chunk, std::vec::Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(error) = self.fill_buf() {
+            return Some(Err(error));
+        }
+
+        if self.buf.is_empty() {
+            return None;
+        }
+
+        let len = self.chunker.cut(&self.buf);
+        let data: std::vec::Vec<u8> = self.buf.drain(..len).collect();
+        let chunk = Chunk {
+            offset: self.offset,
+            len,
+            hash: hash(&data, 0),
+        };
+
+        self.offset += len as u64;
+
+        Some(Ok((chunk, data)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random_data(len: usize, seed: u64) -> std::vec::Vec<u8> {
+        let mut data = std::vec![0u8; len];
+        Mx3Rng::new(seed).fill_bytes(&mut data);
+
+        data
+    }
+
+    #[test]
+    fn test_sizes() {
+        let chunker = Chunker::new(1024, 4096, 16384);
+        let data = random_data(1_000_000, 1);
+        let mut count = 0;
+        let mut total = 0;
+
+        for (chunk, chunk_data) in chunker.chunks(&data) {
+            assert_eq!(chunk.offset, total as u64);
+            assert_eq!(chunk.len, chunk_data.len());
+            assert!(chunk.len <= 16384);
+
+            if chunk.offset + (chunk.len as u64) < data.len() as u64 {
+                assert!(chunk.len >= 1024);
+            }
+
+            count += 1;
+            total += chunk.len;
+        }
+
+        assert_eq!(total, data.len());
+
+        let average = total / count;
+        assert!((3000..6000).contains(&average), "{}", average);
+    }
+
+    #[test]
+    fn test_small_data() {
+        let chunker = Chunker::new(64, 256, 1024);
+
+        assert_eq!(chunker.chunks(&[]).count(), 0);
+        assert_eq!(chunker.cut(&[1; 10]), 10);
+
+        let chunks: std::vec::Vec<_> = chunker.chunks(b"hello").collect();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].0.hash, hash(b"hello", 0));
+    }
+
+    #[test]
+    fn test_max_size() {
+        let chunker = Chunker::new(64, 256, 1024);
+        let data = [0u8; 5000];
+
+        for (chunk, _) in chunker.chunks(&data) {
+            assert!(chunk.len <= 1024);
+        }
+    }
+
+    #[test]
+    fn test_edit_resynchronizes() {
+        let chunker = Chunker::new(256, 1024, 4096);
+        let data = random_data(100_000, 2);
+        let mut edited = data.clone();
+        edited.splice(50_000..50_000, [1, 2, 3]);
+
+        let hashes: std::vec::Vec<u64> = chunker.chunks(&data).map(|(c, _)| c.hash).collect();
+        let edited_hashes: std::vec::Vec<u64> =
+            chunker.chunks(&edited).map(|(c, _)| c.hash).collect();
+
+        let common = edited_hashes
+            .iter()
+            .filter(|hash| hashes.contains(hash))
+            .count();
+
+        assert!(common + 3 >= hashes.len(), "{} {}", common, hashes.len());
+    }
+
+    #[test]
+    fn test_stable() {
+        let chunker = Chunker::new(64, 256, 1024);
+        let data = random_data(2048, 3);
+
+        assert_eq!(chunker.cut(&data), 435);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_read_chunks() {
+        struct SmallReads<'a>(&'a [u8]);
+
+        impl std::io::Read for SmallReads<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let amount = buf.len().min(self.0.len()).min(100);
+                buf[0..amount].copy_from_slice(&self.0[0..amount]);
+                self.0 = &self.0[amount..];
+
+                Ok(amount)
+            }
+        }
+
+        let chunker = Chunker::new(256, 1024, 4096);
+        let data = random_data(50_000, 4);
+
+        let expected: std::vec::Vec<(Chunk, std::vec::Vec<u8>)> = chunker
+            .chunks(&data)
+            .map(|(chunk, chunk_data)| (chunk, chunk_data.to_vec()))
+            .collect();
+        let chunks: std::vec::Vec<(Chunk, std::vec::Vec<u8>)> = chunker
+            .read_chunks(SmallReads(&data))
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(chunks, expected);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_read_chunks_unbounded_max_size() {
+        let chunker = Chunker::new(1, 4, usize::MAX);
+        let data = random_data(1000, 5);
+
+        let len: usize = chunker
+            .read_chunks(&data[..])
+            .map(|result| result.unwrap().0.len)
+            .sum();
+
+        assert_eq!(len, data.len());
+    }
+}
@@ -0,0 +1,676 @@
+//! Sampling of common distributions shared by the `Mx3Rng` generators.
+//!
+//! The generators have inherent methods delegating to these functions so
+//! that `no_std` users do not need the `rand` crate.
+use core::ops::Range;
+
+use rand_core::RngCore;
+
+#[cfg(feature = "alloc")]
+mod alias;
+
+#[cfg(feature = "alloc")]
+pub use alias::AliasTable;
+
+include!(concat!(env!("OUT_DIR"), "/ziggurat_tables.rs"));
+
+/// Implements the sampling methods, which delegate to this module, on a
+/// generator with a `counter` field passed through the mixer.
+macro_rules! impl_sampling_methods {
+    ($rng:ident, $mix:path) => {
+        impl $rng {
+            /// Returns a uniformly distributed integer in the range.
+            ///
+            /// The result is unbiased using Lemire's method which usually needs
+            /// only one output of the generator.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the range is empty.
+            pub fn gen_range_u64(&mut self, range: core::ops::Range<u64>) -> u64 {
+                $crate::distributions::gen_range_u64(self, range)
+            }
+
+            /// Returns a uniformly distributed float in [0, 1) with 53 bits of
+            /// precision.
+            pub fn gen_f64(&mut self) -> f64 {
+                $crate::distributions::gen_f64(self)
+            }
+
+            /// Returns a uniformly distributed float in [0, 1) with 24 bits of
+            /// precision.
+            pub fn gen_f32(&mut self) -> f32 {
+                $crate::distributions::gen_f32(self)
+            }
+
+            /// Returns `true` with probability `p`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `p` is not between 0 and 1 inclusive.
+            pub fn gen_bool(&mut self, p: f64) -> bool {
+                $crate::distributions::gen_bool(self, p)
+            }
+
+            /// Returns a sample of the normal distribution using the ziggurat
+            /// method.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `std_dev` is negative or not finite.
+            pub fn gen_normal(&mut self, mean: f64, std_dev: f64) -> f64 {
+                $crate::distributions::gen_normal(self, mean, std_dev)
+            }
+
+            /// Returns a sample of the exponential distribution with the given rate
+            /// (the inverse of the mean) using the ziggurat method.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `rate` is not positive.
+            pub fn gen_exponential(&mut self, rate: f64) -> f64 {
+                $crate::distributions::gen_exponential(self, rate)
+            }
+
+            /// Shuffles the slice using the Fisher-Yates algorithm.
+            pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+                $crate::distributions::shuffle(self, slice)
+            }
+
+            /// Moves `amount` randomly chosen elements to the front of the slice in
+            /// random order.
+            ///
+            /// Returns the chosen elements and the rest of the slice. The order of the
+            /// rest of the slice is unspecified. If `amount` is greater than the
+            /// length of the slice, the whole slice is shuffled.
+            pub fn partial_shuffle<'a, T>(
+                &mut self,
+                slice: &'a mut [T],
+                amount: usize,
+            ) -> (&'a mut [T], &'a mut [T]) {
+                $crate::distributions::partial_shuffle(self, slice, amount)
+            }
+
+            /// Returns a uniformly chosen element of the slice, or `None` if the
+            /// slice is empty.
+            pub fn choose<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T> {
+                $crate::distributions::choose_index(self, slice.len()).map(|index| &slice[index])
+            }
+
+            /// Returns a uniformly chosen element of the slice, or `None` if the
+            /// slice is empty.
+            pub fn choose_mut<'a, T>(&mut self, slice: &'a mut [T]) -> Option<&'a mut T> {
+                $crate::distributions::choose_index(self, slice.len())
+                    .map(|index| &mut slice[index])
+            }
+
+            /// Returns a uniformly chosen item of the iterator, or `None` if the
+            /// iterator is empty.
+            ///
+            /// The whole iterator is consumed using one output of the generator
+            /// per item.
+            pub fn choose_iter<I: IntoIterator>(&mut self, iter: I) -> Option<I::Item> {
+                $crate::distributions::choose_iter(self, iter)
+            }
+
+            /// Fills the output with items chosen uniformly without replacement from
+            /// the iterator using reservoir sampling.
+            ///
+            /// Returns the number of items written, which is less than the length of
+            /// the output if the iterator has fewer items. The order of the items in
+            /// the output is not random; use [`Self::shuffle()`] if needed.
+            pub fn reservoir_sample<I: IntoIterator>(
+                &mut self,
+                iter: I,
+                output: &mut [I::Item],
+            ) -> usize {
+                $crate::distributions::reservoir_sample(self, iter, output)
+            }
+
+            /// Returns an iterator over the outputs of [`RngCore::next_u64()`].
+            ///
+            /// See [`RngIter`](crate::RngIter) for skipping and bounding the outputs.
+            pub fn iter_u64(&mut self) -> $crate::RngIter<'_, u64> {
+                $crate::RngIter::new(&mut self.counter, $mix)
+            }
+
+            /// Returns an iterator over the outputs of [`RngCore::next_u32()`].
+            ///
+            /// See [`RngIter`](crate::RngIter) for skipping and bounding the outputs.
+            pub fn iter_u32(&mut self) -> $crate::RngIter<'_, u32> {
+                $crate::RngIter::new(&mut self.counter, |counter| $mix(counter) as u32)
+            }
+
+            /// Returns an iterator over the outputs of [`Self::gen_f64()`].
+            ///
+            /// See [`RngIter`](crate::RngIter) for skipping and bounding the outputs.
+            pub fn iter_f64(&mut self) -> $crate::RngIter<'_, f64> {
+                $crate::RngIter::new(&mut self.counter, |counter| {
+                    $crate::distributions::to_f64($mix(counter))
+                })
+            }
+        }
+
+        #[cfg(test)]
+        mod sampling_tests {
+            use rand_core::RngCore;
+
+            use super::$rng;
+
+            #[test]
+            fn test_distributions() {
+                let mut rng = $rng::new(1);
+
+                assert!(rng.gen_range_u64(10..20) >= 10);
+                assert!(rng.gen_f64() < 1.0);
+                assert!(rng.gen_f32() < 1.0);
+                assert!(!rng.gen_bool(0.0));
+                assert!(rng.gen_normal(0.0, 1.0).is_finite());
+                assert!(rng.gen_exponential(1.0) >= 0.0);
+            }
+
+            #[test]
+            fn test_shuffle_and_choose() {
+                let mut rng = $rng::new(1);
+
+                let mut values = [1, 2, 3];
+                rng.shuffle(&mut values);
+                assert_eq!(rng.partial_shuffle(&mut values, 2).0.len(), 2);
+                assert!(rng.choose(&values).is_some());
+                assert!(rng.choose_mut(&mut values).is_some());
+                assert!(rng.choose_iter(0..3).is_some());
+                assert_eq!(rng.reservoir_sample(0..10, &mut values), 3);
+            }
+
+            #[test]
+            fn test_iter() {
+                let mut rng = $rng::new(1);
+                let mut expected = rng.clone();
+
+                assert_eq!(rng.iter_u64().next(), Some(expected.next_u64()));
+
+                expected.next_u64();
+                assert_eq!(rng.iter_u32().nth(1), Some(expected.next_u32()));
+                assert_eq!(rng.state(), expected.state());
+            }
+        }
+    };
+}
+
+pub(crate) use impl_sampling_methods;
+
+/// Returns a uniformly distributed integer in the range using Lemire's
+/// method from "Fast Random Integer Generation in an Interval".
+pub(crate) fn gen_range_u64<R: RngCore>(rng: &mut R, range: Range<u64>) -> u64 {
+    assert!(range.start < range.end, "range must not be empty");
+
+    let span = range.end - range.start;
+    let mut product = rng.next_u64() as u128 * span as u128;
+
+    if (product as u64) < span {
+        let threshold = span.wrapping_neg() % span;
+
+        while (product as u64) < threshold {
+            product = rng.next_u64() as u128 * span as u128;
+        }
+    }
+
+    range.start + (product >> 64) as u64
+}
+
+/// Returns a uniformly distributed float in [0, 1) with 53 bits of
+/// precision.
+pub(crate) fn gen_f64<R: RngCore>(rng: &mut R) -> f64 {
+    to_f64(rng.next_u64())
+}
+
+/// Converts the upper 53 bits of the integer to a float in [0, 1).
+pub(crate) fn to_f64(x: u64) -> f64 {
+    (x >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+}
+
+/// Returns a uniformly distributed float in [0, 1) with 24 bits of
+/// precision.
+pub(crate) fn gen_f32<R: RngCore>(rng: &mut R) -> f32 {
+    (rng.next_u64() >> 40) as f32 * (1.0 / (1u32 << 24) as f32)
+}
+
+/// Returns a uniformly distributed float in (0, 1).
+fn gen_open01<R: RngCore>(rng: &mut R) -> f64 {
+    ((rng.next_u64() >> 12) as f64 + 0.5) * (1.0 / (1u64 << 52) as f64)
+}
+
+pub(crate) fn gen_bool<R: RngCore>(rng: &mut R, p: f64) -> bool {
+    assert!(
+        (0.0..=1.0).contains(&p),
+        "p must be between 0 and 1 inclusive"
+    );
+
+    if p == 1.0 {
+        return true;
+    }
+
+    // 2^64 times p, which is less than 2^64 unless p is 1.
+    let threshold = (p * 18446744073709551616.0) as u64;
+
+    rng.next_u64() < threshold
+}
+
+pub(crate) fn gen_normal<R: RngCore>(rng: &mut R, mean: f64, std_dev: f64) -> f64 {
+    assert!(
+        std_dev >= 0.0 && std_dev.is_finite(),
+        "std_dev must be finite and not negative"
+    );
+
+    mean + std_dev * standard_normal(rng)
+}
+
+pub(crate) fn gen_exponential<R: RngCore>(rng: &mut R, rate: f64) -> f64 {
+    assert!(rate > 0.0, "rate must be positive");
+
+    standard_exponential(rng) / rate
+}
+
+fn standard_normal<R: RngCore>(rng: &mut R) -> f64 {
+    ziggurat(
+        rng,
+        true,
+        &ZIG_NORMAL_X,
+        &ZIG_NORMAL_F,
+        |x| libm::exp(-x * x / 2.0),
+        |rng, u| {
+            // Sample from the tail beyond R using Marsaglia's method.
+            loop {
+                let x = libm::log(gen_open01(rng)) / ZIG_NORMAL_R;
+                let y = libm::log(gen_open01(rng));
+
+                if -2.0 * y >= x * x {
+                    return if u < 0.0 {
+                        x - ZIG_NORMAL_R
+                    } else {
+                        ZIG_NORMAL_R - x
+                    };
+                }
+            }
+        },
+    )
+}
+
+fn standard_exponential<R: RngCore>(rng: &mut R) -> f64 {
+    ziggurat(
+        rng,
+        false,
+        &ZIG_EXP_X,
+        &ZIG_EXP_F,
+        |x| libm::exp(-x),
+        |rng, _| ZIG_EXP_R - libm::log(gen_open01(rng)),
+    )
+}
+
+/// Shuffles the slice using the Fisher-Yates algorithm.
+pub(crate) fn shuffle<R: RngCore, T>(rng: &mut R, slice: &mut [T]) {
+    for index in (1..slice.len()).rev() {
+        let other = gen_range_u64(rng, 0..index as u64 + 1) as usize;
+        slice.swap(index, other);
+    }
+}
+
+/// Moves `amount` randomly chosen elements to the front of the slice in
+/// random order, using the first iterations of the Fisher-Yates algorithm.
+pub(crate) fn partial_shuffle<'a, R: RngCore, T>(
+    rng: &mut R,
+    slice: &'a mut [T],
+    amount: usize,
+) -> (&'a mut [T], &'a mut [T]) {
+    let amount = amount.min(slice.len());
+
+    for index in 0..amount {
+        let other = gen_range_u64(rng, index as u64..slice.len() as u64) as usize;
+        slice.swap(index, other);
+    }
+
+    slice.split_at_mut(amount)
+}
+
+pub(crate) fn choose_index<R: RngCore>(rng: &mut R, len: usize) -> Option<usize> {
+    if len == 0 {
+        None
+    } else {
+        Some(gen_range_u64(rng, 0..len as u64) as usize)
+    }
+}
+
+/// Fills the output with a uniform sample of the items without replacement
+/// using reservoir sampling (Algorithm R).
+pub(crate) fn reservoir_sample<R: RngCore, I: IntoIterator>(
+    rng: &mut R,
+    iter: I,
+    output: &mut [I::Item],
+) -> usize {
+    let mut count = 0usize;
+
+    for item in iter {
+        if count < output.len() {
+            output[count] = item;
+        } else {
+            let index = gen_range_u64(rng, 0..count as u64 + 1) as usize;
+
+            if index < output.len() {
+                output[index] = item;
+            }
+        }
+
+        count += 1;
+    }
+
+    count.min(output.len())
+}
+
+pub(crate) fn choose_iter<R: RngCore, I: IntoIterator>(rng: &mut R, iter: I) -> Option<I::Item> {
+    let mut chosen = None;
+
+    for (index, item) in iter.into_iter().enumerate() {
+        if gen_range_u64(rng, 0..index as u64 + 1) == 0 {
+            chosen = Some(item);
+        }
+    }
+
+    chosen
+}
+
+/// Samples using the ziggurat method described in "The Ziggurat Method for
+/// Generating Random Variables" by Marsaglia and Tsang.
+fn ziggurat<R: RngCore>(
+    rng: &mut R,
+    symmetric: bool,
+    x_table: &[f64; 257],
+    f_table: &[f64; 257],
+    pdf: fn(f64) -> f64,
+    tail: fn(&mut R, f64) -> f64,
+) -> f64 {
+    loop {
+        // The lower 8 bits choose the layer and the upper 52 bits give the
+        // position within the layer.
+        let bits = rng.next_u64();
+        let layer = (bits & 0xff) as usize;
+        let u = if symmetric {
+            f64::from_bits(bits >> 12 | 0x4000_0000_0000_0000) - 3.0
+        } else {
+            f64::from_bits(bits >> 12 | 0x3ff0_0000_0000_0000) - 1.0
+        };
+
+        let x = u * x_table[layer];
+        let test_x = if symmetric { x.abs() } else { x };
+
+        if test_x < x_table[layer + 1] {
+            return x;
+        }
+
+        if layer == 0 {
+            return tail(rng, u);
+        }
+
+        if f_table[layer + 1] + (f_table[layer] - f_table[layer + 1]) * gen_f64(rng) < pdf(x) {
+            return x;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v3::Mx3Rng;
+
+    fn mean_and_variance(samples: impl Iterator<Item = f64>) -> (f64, f64) {
+        let mut count = 0.0;
+        let mut sum = 0.0;
+        let mut sum_squares = 0.0;
+
+        for sample in samples {
+            count += 1.0;
+            sum += sample;
+            sum_squares += sample * sample;
+        }
+
+        let mean = sum / count;
+
+        (mean, sum_squares / count - mean * mean)
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let mut rng = Mx3Rng::new(6);
+        let mut counts = [[0u32; 4]; 4];
+
+        for _ in 0..40000 {
+            let mut values = [0, 1, 2, 3];
+            shuffle(&mut rng, &mut values);
+
+            for (position, &value) in values.iter().enumerate() {
+                counts[position][value] += 1;
+            }
+        }
+
+        for row in counts {
+            for count in row {
+                assert!((9500..10500).contains(&count), "{:?}", counts);
+            }
+        }
+
+        shuffle::<_, u8>(&mut rng, &mut []);
+    }
+
+    #[test]
+    fn test_shuffle_stable() {
+        let mut values = [0, 1, 2, 3, 4, 5, 6, 7];
+        shuffle(&mut Mx3Rng::new(1), &mut values);
+
+        assert_eq!(values, [6, 4, 5, 1, 3, 0, 2, 7]);
+    }
+
+    #[test]
+    fn test_partial_shuffle() {
+        let mut rng = Mx3Rng::new(7);
+        let mut values = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+        let (chosen, rest) = partial_shuffle(&mut rng, &mut values, 3);
+        assert_eq!(chosen.len(), 3);
+        assert_eq!(rest.len(), 7);
+
+        values.sort_unstable();
+        assert_eq!(values, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        let (chosen, rest) = partial_shuffle(&mut rng, &mut values, 20);
+        assert_eq!(chosen.len(), 10);
+        assert!(rest.is_empty());
+
+        let mut counts = [0u32; 10];
+
+        for _ in 0..10000 {
+            let mut values = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+            counts[partial_shuffle(&mut rng, &mut values, 1).0[0]] += 1;
+        }
+
+        for count in counts {
+            assert!((900..1100).contains(&count), "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn test_choose() {
+        let mut rng = Mx3Rng::new(8);
+
+        assert_eq!(choose_index(&mut rng, 0), None);
+        assert_eq!(choose_index(&mut rng, 1), Some(0));
+        assert_eq!(choose_iter(&mut rng, 0..0), None);
+        assert_eq!(choose_iter(&mut rng, 5..6), Some(5));
+
+        let mut counts = [0u32; 5];
+
+        for _ in 0..10000 {
+            counts[choose_iter(&mut rng, 0..5).unwrap()] += 1;
+        }
+
+        for count in counts {
+            assert!((1800..2200).contains(&count), "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn test_reservoir_sample() {
+        let mut rng = Mx3Rng::new(9);
+        let mut output = [0u32; 4];
+
+        assert_eq!(reservoir_sample(&mut rng, 0..2, &mut output), 2);
+        assert_eq!(output[0..2], [0, 1]);
+
+        let mut counts = [0u32; 20];
+
+        for _ in 0..10000 {
+            assert_eq!(reservoir_sample(&mut rng, 0..20, &mut output), 4);
+
+            for &value in &output {
+                counts[value as usize] += 1;
+            }
+
+            let mut sorted = output;
+            sorted.sort_unstable();
+            assert!(sorted.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+
+        // Each item is sampled with probability 4/20.
+        for count in counts {
+            assert!((1850..2150).contains(&count), "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn test_tables() {
+        let (normal_x, normal_f) = (ZIG_NORMAL_X, ZIG_NORMAL_F);
+        let exp_x = ZIG_EXP_X;
+
+        assert_eq!(normal_x[1], ZIG_NORMAL_R);
+        assert_eq!(normal_x[256], 0.0);
+        assert_eq!(normal_f[256], 1.0);
+        assert_eq!(exp_x[1], ZIG_EXP_R);
+
+        for index in 1..256 {
+            assert!(normal_x[index] > normal_x[index + 1]);
+            assert!(exp_x[index] > exp_x[index + 1]);
+        }
+
+        // The recurrence should close the ziggurat near 0.
+        assert!(normal_x[255] < 0.3, "{}", normal_x[255]);
+        assert!(exp_x[255] < 0.1, "{}", exp_x[255]);
+    }
+
+    #[test]
+    fn test_gen_range_u64() {
+        let mut rng = Mx3Rng::new(1);
+        let mut counts = [0u32; 6];
+
+        for _ in 0..60000 {
+            let value = gen_range_u64(&mut rng, 10..16);
+            counts[(value - 10) as usize] += 1;
+        }
+
+        for count in counts {
+            assert!((9500..10500).contains(&count), "{:?}", counts);
+        }
+
+        assert_eq!(gen_range_u64(&mut rng, 5..6), 5);
+
+        for _ in 0..1000 {
+            assert!(gen_range_u64(&mut rng, 0..u64::MAX) < u64::MAX);
+            assert!(gen_range_u64(&mut rng, (1 << 63) + 1..u64::MAX) > 1 << 63);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gen_range_u64_empty() {
+        gen_range_u64(&mut Mx3Rng::new(1), 5..5);
+    }
+
+    #[test]
+    fn test_gen_floats() {
+        let mut rng = Mx3Rng::new(2);
+
+        for _ in 0..10000 {
+            assert!((0.0..1.0).contains(&gen_f64(&mut rng)));
+            assert!((0.0..1.0).contains(&gen_f32(&mut rng)));
+            assert!(gen_open01(&mut rng) > 0.0);
+        }
+
+        let (mean, variance) = mean_and_variance((0..100000).map(|_| gen_f64(&mut rng)));
+        assert!((mean - 0.5).abs() < 0.01, "{}", mean);
+        assert!((variance - 1.0 / 12.0).abs() < 0.01, "{}", variance);
+
+        let (mean, _) = mean_and_variance((0..100000).map(|_| gen_f32(&mut rng) as f64));
+        assert!((mean - 0.5).abs() < 0.01, "{}", mean);
+    }
+
+    #[test]
+    fn test_gen_bool() {
+        let mut rng = Mx3Rng::new(3);
+
+        assert!((0..1000).all(|_| !gen_bool(&mut rng, 0.0)));
+        assert!((0..1000).all(|_| gen_bool(&mut rng, 1.0)));
+
+        let count = (0..100000).filter(|_| gen_bool(&mut rng, 0.3)).count();
+        assert!((29000..31000).contains(&count), "{}", count);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gen_bool_invalid() {
+        gen_bool(&mut Mx3Rng::new(1), 1.5);
+    }
+
+    #[test]
+    fn test_gen_normal() {
+        let mut rng = Mx3Rng::new(4);
+        let values: std::vec::Vec<f64> = (0..200000).map(|_| standard_normal(&mut rng)).collect();
+        let (mean, variance) = mean_and_variance(values.iter().copied());
+
+        assert!(mean.abs() < 0.01, "{}", mean);
+        assert!((variance - 1.0).abs() < 0.02, "{}", variance);
+
+        // About 0.27% of the samples are beyond 3 standard deviations and
+        // 0.026% are in the tail beyond R.
+        let beyond_3 = values.iter().filter(|x| x.abs() > 3.0).count();
+        assert!((400..700).contains(&beyond_3), "{}", beyond_3);
+
+        let tail = values.iter().filter(|x| x.abs() > ZIG_NORMAL_R).count();
+        assert!((25..80).contains(&tail), "{}", tail);
+
+        let negative = values.iter().filter(|&&x| x < 0.0).count();
+        assert!((99000..101000).contains(&negative), "{}", negative);
+
+        let (mean, variance) =
+            mean_and_variance((0..100000).map(|_| gen_normal(&mut rng, 10.0, 2.0)));
+        assert!((mean - 10.0).abs() < 0.05, "{}", mean);
+        assert!((variance - 4.0).abs() < 0.1, "{}", variance);
+    }
+
+    #[test]
+    fn test_gen_exponential() {
+        let mut rng = Mx3Rng::new(5);
+
+        let values: std::vec::Vec<f64> = (0..200000)
+            .map(|_| standard_exponential(&mut rng))
+            .collect();
+        let (mean, variance) = mean_and_variance(values.iter().copied());
+
+        assert!(values.iter().all(|&x| x >= 0.0));
+        assert!((mean - 1.0).abs() < 0.01, "{}", mean);
+        assert!((variance - 1.0).abs() < 0.03, "{}", variance);
+
+        // About e^-3 of the samples are greater than 3.
+        let beyond_3 = values.iter().filter(|&&x| x > 3.0).count();
+        assert!((9500..10400).contains(&beyond_3), "{}", beyond_3);
+
+        let (mean, _) = mean_and_variance((0..100000).map(|_| gen_exponential(&mut rng, 4.0)));
+        assert!((mean - 0.25).abs() < 0.005, "{}", mean);
+    }
+}
@@ -0,0 +1,149 @@
+use alloc::vec::Vec;
+
+use rand_core::RngCore;
+
+use super::{gen_f64, gen_range_u64};
+
+/// Table for sampling indices with given weights using the alias method.
+///
+/// Building the table takes O(n) time using Vose's algorithm and each sample
+/// takes O(1) time and two outputs of the generator.
+///
+/// ```rust
+/// use mx3::v3::Mx3Rng;
+/// use mx3::AliasTable;
+///
+/// let table = AliasTable::new(&[1.0, 2.0, 7.0]).unwrap();
+/// let mut rng = Mx3Rng::new(1);
+///
+/// let index = table.sample(&mut rng);
+/// assert!(index < 3);
+/// ```
+///
+/// The samples are [stable](crate#stability) in the same way as the
+/// sampling methods of the generators.
+#[derive(Clone, Debug)]
+pub struct AliasTable {
+    probabilities: Vec<f64>,
+    aliases: Vec<usize>,
+}
+
+impl AliasTable {
+    /// Creates the table for the weights.
+    ///
+    /// Returns `None` if there are no weights, a weight is negative or not
+    /// finite, or all the weights are 0.
+    pub fn new(weights: &[f64]) -> Option<Self> {
+        if weights
+            .iter()
+            .any(|&weight| !(weight >= 0.0 && weight.is_finite()))
+        {
+            return None;
+        }
+
+        let sum: f64 = weights.iter().sum();
+
+        if !(sum > 0.0 && sum.is_finite()) {
+            return None;
+        }
+
+        let len = weights.len();
+        let mut probabilities: Vec<f64> = weights
+            .iter()
+            .map(|&weight| weight * len as f64 / sum)
+            .collect();
+        let mut aliases: Vec<usize> = (0..len).collect();
+        let mut small = Vec::new();
+        let mut large = Vec::new();
+
+        for (index, &probability) in probabilities.iter().enumerate() {
+            if probability < 1.0 {
+                small.push(index);
+            } else {
+                large.push(index);
+            }
+        }
+
+        while let (Some(&less), Some(&more)) = (small.last(), large.last()) {
+            small.pop();
+            aliases[less] = more;
+            probabilities[more] -= 1.0 - probabilities[less];
+
+            if probabilities[more] < 1.0 {
+                large.pop();
+                small.push(more);
+            }
+        }
+
+        // Remaining entries are 1 apart from rounding errors.
+        for index in small.into_iter().chain(large) {
+            probabilities[index] = 1.0;
+        }
+
+        Some(Self {
+            probabilities,
+            aliases,
+        })
+    }
+
+    /// Returns the number of weights.
+    pub fn num_weights(&self) -> usize {
+        self.probabilities.len()
+    }
+
+    /// Returns a random index with probability proportional to its weight.
+    pub fn sample<R: RngCore>(&self, rng: &mut R) -> usize {
+        let index = gen_range_u64(rng, 0..self.probabilities.len() as u64) as usize;
+
+        if gen_f64(rng) < self.probabilities[index] {
+            index
+        } else {
+            self.aliases[index]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v3::Mx3Rng;
+
+    #[test]
+    fn test_sample() {
+        let weights = [1.0, 0.0, 2.0, 3.0, 4.0];
+        let table = AliasTable::new(&weights).unwrap();
+        let mut rng = Mx3Rng::new(1);
+        let mut counts = [0u32; 5];
+
+        assert_eq!(table.num_weights(), 5);
+
+        for _ in 0..100000 {
+            counts[table.sample(&mut rng)] += 1;
+        }
+
+        assert_eq!(counts[1], 0);
+
+        for (count, weight) in counts.iter().zip(weights) {
+            let expected = weight * 10000.0;
+            assert!((*count as f64 - expected).abs() < 500.0, "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn test_single() {
+        let table = AliasTable::new(&[0.5]).unwrap();
+        let mut rng = Mx3Rng::new(1);
+
+        assert!((0..100).all(|_| table.sample(&mut rng) == 0));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(AliasTable::new(&[]).is_none());
+        assert!(AliasTable::new(&[0.0, 0.0]).is_none());
+        assert!(AliasTable::new(&[1.0, -1.0]).is_none());
+        assert!(AliasTable::new(&[1.0, f64::NAN]).is_none());
+        assert!(AliasTable::new(&[1.0, f64::INFINITY]).is_none());
+        assert!(AliasTable::new(&[f64::MAX, f64::MAX]).is_none());
+    }
+}
@@ -1,5 +1,5 @@
 use core::fmt::{Debug, Formatter};
-use core::hash::Hasher;
+use core::hash::{BuildHasher, Hasher};
 
 /// Hasher for computing a hash digest of a stream of bytes.
 ///
@@ -20,6 +20,15 @@ pub struct Mx3Hasher {
     state: u64,
     buf: [u8; 1024],
     buf_filled: usize,
+    keys: Option<Keys>,
+    len: u64,
+}
+
+/// Round keys of a hasher built by [`RandomMx3State`].
+#[derive(Clone)]
+struct Keys {
+    word: [u64; 3],
+    finish: [u64; 3],
 }
 
 impl Mx3Hasher {
@@ -33,17 +42,54 @@ impl Mx3Hasher {
             state: crate::v3::mix(seed),
             buf: [0u8; 1024],
             buf_filled: 0,
+            keys: None,
+            len: 0,
+        }
+    }
+
+    /// Construct a hasher that mixes every 8-byte word of the stream with
+    /// the key.
+    ///
+    /// The words are chained in order, the last word is padded with zeros
+    /// and the length is mixed in with a second key derived from the key,
+    /// so collisions depend on the key.
+    #[cfg(feature = "std")]
+    fn keyed(key: u64) -> Self {
+        let keys = Keys {
+            word: crate::v3::round_keys(key),
+            finish: crate::v3::round_keys(crate::v3::mix(key)),
+        };
+
+        Self {
+            seed: 0,
+            state: 0,
+            buf: [0u8; 1024],
+            buf_filled: 0,
+            keys: Some(keys),
+            len: 0,
         }
     }
 }
 
+fn mix_words(mut state: u64, bytes: &[u8], round_keys: &[u64; 3]) -> u64 {
+    for chunk in bytes.chunks(8) {
+        let mut word = [0u8; 8];
+        word[..chunk.len()].copy_from_slice(chunk);
+
+        state = crate::v3::mix_round_keys(state ^ u64::from_le_bytes(word), round_keys);
+    }
+
+    state
+}
+
 impl Hasher for Mx3Hasher {
     fn write(&mut self, bytes: &[u8]) {
         let mut remain = bytes;
+        self.len = self.len.wrapping_add(bytes.len() as u64);
 
         while !remain.is_empty() {
-            let amount = bytes.len().min(self.buf.len() - self.buf_filled);
-            let (left, right) = bytes.split_at(amount);
+            let amount = remain.len().min(self.buf.len() - self.buf_filled);
+            let (left, right) = remain.split_at(amount);
 
             self.buf[self.buf_filled..self.buf_filled + amount].copy_from_slice(left);
             self.buf_filled += amount;
@@ -51,7 +97,10 @@ impl Hasher for Mx3Hasher {
             debug_assert!(self.buf_filled <= self.buf.len());
 
             if self.buf_filled == self.buf.len() {
-                self.state ^= crate::v3::hash(&self.buf, self.seed);
+                match &self.keys {
+                    Some(keys) => self.state = mix_words(self.state, &self.buf, &keys.word),
+                    None => self.state ^= crate::v3::hash(&self.buf, self.seed),
+                }
                 self.buf_filled = 0;
             }
 
@@ -60,6 +109,12 @@ impl Hasher for Mx3Hasher {
     }
 
     fn finish(&self) -> u64 {
+        if let Some(keys) = &self.keys {
+            let state = mix_words(self.state, &self.buf[0..self.buf_filled], &keys.word);
+
+            return crate::v3::mix_round_keys(state ^ self.len, &keys.finish);
+        }
+
         let mut output = self.state;
 
         if self.buf_filled > 0 {
@@ -82,6 +137,110 @@ impl Default for Mx3Hasher {
     }
 }
 
+/// Builder of [`Mx3Hasher`] instances with a fixed seed.
+///
+/// Maps using this builder hash keys the same way in every process, which is
+/// useful for deterministic output.
+///
+/// The hasher does not resist hash flooding: an attacker who controls the
+/// keys can cause many collisions. Use
+/// [`std::collections::hash_map::RandomState`] for maps with untrusted keys.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mx3BuildHasher {
+    seed: u64,
+}
+
+impl Mx3BuildHasher {
+    /// Creates a builder of hashers with the given seed.
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// Returns the seed.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for Mx3BuildHasher {
+    /// Creates a builder of hashers with the same seed as
+    /// [`Mx3Hasher::default()`].
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl BuildHasher for Mx3BuildHasher {
+    type Hasher = Mx3Hasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        Mx3Hasher::new(self.seed)
+    }
+}
+
+/// Builder of [`Mx3Hasher`] instances with a random key.
+///
+/// Each builder has a different key derived from
+/// [`std::collections::hash_map::RandomState`], so the iteration order of
+/// maps differs between builders and processes.
+///
+/// Unlike [`Mx3BuildHasher`], the hashers mix every 8-byte word of the
+/// stream in order with [`crate::v3::mix_keyed()`] under the key, so keys
+/// crafted to collide under one key do not collide under another. This
+/// makes hash flooding harder for an attacker who cannot observe the hashes.
+///
+/// ```rust
+/// use std::collections::HashMap;
+///
+/// use mx3::RandomMx3State;
+///
+/// let mut map = HashMap::with_hasher(RandomMx3State::new());
+/// map.insert("apple", 1);
+///
+/// assert_eq!(map.get("apple"), Some(&1));
+/// ```
+///
+/// This hasher is *not* cryptographically secure.
+#[cfg_attr(docsrs, doc(cfg(all(feature = "hasher", feature = "std"))))]
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct RandomMx3State {
+    key: u64,
+}
+
+#[cfg(feature = "std")]
+impl RandomMx3State {
+    /// Creates a builder of hashers with a new random key.
+    pub fn new() -> Self {
+        let key = std::collections::hash_map::RandomState::new().hash_one(0u64);
+
+        Self { key }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for RandomMx3State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl BuildHasher for RandomMx3State {
+    type Hasher = Mx3Hasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        Mx3Hasher::keyed(self.key)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Debug for RandomMx3State {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "RandomMx3State {{...}}")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,7 +254,42 @@ mod tests {
             hasher.write(input);
         }
 
-        assert_eq!(hasher.finish(), 8878623092709932526);
+        assert_eq!(hasher.finish(), 5540318612383192274);
+    }
+
+    #[test]
+    fn test_mx3hasher_large_write() {
+        let input = [0xabu8; 3000];
+
+        let mut hasher = Mx3Hasher::new(123456789);
+        hasher.write(&input);
+
+        let mut hasher2 = Mx3Hasher::new(123456789);
+        for chunk in input.chunks(7) {
+            hasher2.write(chunk);
+        }
+
+        assert_eq!(hasher.finish(), hasher2.finish());
+    }
+
+    #[test]
+    fn test_mx3hasher_write_across_buffer() {
+        // Regression test for writes that refill the buffer copying from the
+        // start of the input instead of the remaining bytes.
+        let mut input = [0u8; 1500];
+        for (index, value) in input.iter_mut().enumerate() {
+            *value = index as u8 ^ (index >> 8) as u8;
+        }
+
+        let mut hasher = Mx3Hasher::new(123456789);
+        hasher.write(&input[..1000]);
+        hasher.write(&input[1000..]);
+
+        let expected = crate::v3::mix(123456789)
+            ^ crate::v3::hash(&input[..1024], 123456789)
+            ^ crate::v3::hash(&input[1024..], 123456789);
+
+        assert_eq!(hasher.finish(), expected);
     }
 
     #[test]
@@ -121,4 +315,79 @@ mod tests {
 
         assert_eq!(hasher.finish(), hasher2.finish());
     }
+
+    #[test]
+    fn test_build_hasher() {
+        let builder = Mx3BuildHasher::new(123456789);
+        assert_eq!(builder.seed(), 123456789);
+
+        let mut hasher = Mx3Hasher::new(123456789);
+        hasher.write_u32(5);
+        assert_eq!(builder.hash_one(5u32), hasher.finish());
+
+        assert_eq!(
+            Mx3BuildHasher::default().hash_one(5u32),
+            Mx3BuildHasher::new(1).hash_one(5u32)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_random_state() {
+        let state1 = RandomMx3State::new();
+        let state2 = RandomMx3State::new();
+
+        assert_eq!(state1.hash_one("apple"), state1.hash_one("apple"));
+        assert_eq!(state1.clone().hash_one("apple"), state1.hash_one("apple"));
+        assert_ne!(state1.hash_one("apple"), state2.hash_one("apple"));
+
+        let mut map = std::collections::HashMap::with_hasher(RandomMx3State::default());
+        map.insert(1, 2);
+        assert_eq!(map[&1], 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_random_state_large_write() {
+        let input = [0xabu8; 3000];
+        let state = RandomMx3State::new();
+
+        let mut hasher = state.build_hasher();
+        hasher.write(&input);
+
+        let mut hasher2 = state.build_hasher();
+        for chunk in input.chunks(7) {
+            hasher2.write(chunk);
+        }
+
+        assert_eq!(hasher.finish(), hasher2.finish());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_random_state_order_and_length() {
+        fn hash<B: BuildHasher>(builder: &B, bytes: &[u8]) -> u64 {
+            let mut hasher = builder.build_hasher();
+            hasher.write(bytes);
+            hasher.finish()
+        }
+
+        // Swapping the 1024-byte blocks collides under every seed of
+        // `Mx3Hasher::new()` because it combines the blocks with XOR.
+        let mut input = [0u8; 2048];
+        input[..1024].fill(1);
+        let mut swapped = [0u8; 2048];
+        swapped[1024..].fill(1);
+
+        let builder = Mx3BuildHasher::new(5);
+        assert_eq!(hash(&builder, &input), hash(&builder, &swapped));
+
+        let state = RandomMx3State::new();
+        assert_ne!(hash(&state, &input), hash(&state, &swapped));
+        assert_ne!(
+            hash(&state, &input[1016..1032]),
+            hash(&state, &swapped[1016..1032])
+        );
+        assert_ne!(hash(&state, b"a"), hash(&state, b"a\0"));
+    }
 }
@@ -0,0 +1,227 @@
+use core::fmt::{Debug, Formatter};
+use core::iter::FusedIterator;
+
+/// Iterator over the outputs of a generator.
+///
+/// The iterator is returned by methods such as
+/// [`crate::v3::Mx3Rng::iter_u64()`]. It advances the generator as it is
+/// consumed and never ends. [`Iterator::nth()`] skips outputs in O(1) time
+/// by jumping the generator's counter.
+///
+/// Use [`Self::take_exact()`] for an iterator over a bounded number of
+/// outputs that can also be iterated from the back.
+pub struct RngIter<'a, T> {
+    counter: &'a mut u64,
+    output: fn(u64) -> T,
+}
+
+impl<'a, T> RngIter<'a, T> {
+    pub(crate) fn new(counter: &'a mut u64, output: fn(u64) -> T) -> Self {
+        Self { counter, output }
+    }
+
+    /// Returns an iterator over the next `len` outputs.
+    ///
+    /// The generator is advanced past the outputs immediately.
+    pub fn take_exact(self, len: u64) -> BoundedRngIter<T> {
+        let front = *self.counter;
+        *self.counter = self.counter.wrapping_add(len);
+
+        BoundedRngIter {
+            front,
+            remaining: len,
+            output: self.output,
+        }
+    }
+}
+
+impl<T> Iterator for RngIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = (self.output)(*self.counter);
+        *self.counter = self.counter.wrapping_add(1);
+
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        *self.counter = self.counter.wrapping_add(n as u64);
+        self.next()
+    }
+}
+
+impl<T> FusedIterator for RngIter<'_, T> {}
+
+impl<T> Debug for RngIter<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "RngIter {{...}}")
+    }
+}
+
+/// Iterator over a bounded number of outputs of a generator.
+///
+/// The iterator is returned by [`RngIter::take_exact()`]. It does not
+/// borrow the generator. [`Iterator::nth()`] and
+/// [`DoubleEndedIterator::nth_back()`] skip outputs in O(1) time.
+#[derive(Clone)]
+pub struct BoundedRngIter<T> {
+    front: u64,
+    remaining: u64,
+    output: fn(u64) -> T,
+}
+
+impl<T> BoundedRngIter<T> {
+    /// Returns the number of remaining outputs.
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+}
+
+impl<T> Iterator for BoundedRngIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let value = (self.output)(self.front);
+        self.front = self.front.wrapping_add(1);
+        self.remaining -= 1;
+
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.remaining) {
+            Ok(remaining) => (remaining, Some(remaining)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if self.remaining <= n as u64 {
+            self.front = self.front.wrapping_add(self.remaining);
+            self.remaining = 0;
+            return None;
+        }
+
+        self.front = self.front.wrapping_add(n as u64);
+        self.remaining -= n as u64;
+        self.next()
+    }
+}
+
+impl<T> DoubleEndedIterator for BoundedRngIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        self.remaining -= 1;
+
+        Some((self.output)(self.front.wrapping_add(self.remaining)))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        if self.remaining <= n as u64 {
+            self.remaining = 0;
+            return None;
+        }
+
+        self.remaining -= n as u64;
+        self.next_back()
+    }
+}
+
+impl<T> FusedIterator for BoundedRngIter<T> {}
+
+impl<T> Debug for BoundedRngIter<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "BoundedRngIter {{...}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_core::RngCore;
+
+    use crate::v3::Mx3Rng;
+
+    #[test]
+    fn test_iter_u64() {
+        let mut rng = Mx3Rng::new(1);
+        let mut expected = rng.clone();
+
+        for value in rng.iter_u64().take(100) {
+            assert_eq!(value, expected.next_u64());
+        }
+
+        assert_eq!(rng.state(), expected.state());
+        assert_eq!(rng.iter_u64().size_hint(), (usize::MAX, None));
+    }
+
+    #[test]
+    fn test_nth() {
+        let mut rng = Mx3Rng::new(2);
+        let mut expected = rng.clone();
+
+        for _ in 0..1000 {
+            expected.next_u64();
+        }
+
+        assert_eq!(rng.iter_u64().nth(1000), Some(expected.next_u64()));
+        assert_eq!(rng.state(), expected.state());
+
+        let mut rng = Mx3Rng::resume(u64::MAX);
+        assert_eq!(rng.iter_u64().nth(1), Some(crate::v3::mix(0)));
+    }
+
+    #[test]
+    fn test_take_exact() {
+        let mut rng = Mx3Rng::new(3);
+        let mut expected = rng.clone();
+        let values: std::vec::Vec<u64> = (0..10).map(|_| expected.next_u64()).collect();
+
+        let mut iter = rng.iter_u64().take_exact(10);
+        assert_eq!(rng.state(), expected.state());
+        assert_eq!(iter.size_hint(), (10, Some(10)));
+
+        assert_eq!(iter.next(), Some(values[0]));
+        assert_eq!(iter.next_back(), Some(values[9]));
+        assert_eq!(iter.nth(2), Some(values[3]));
+        assert_eq!(iter.nth_back(1), Some(values[7]));
+        assert_eq!(iter.remaining(), 3);
+
+        let rest: std::vec::Vec<u64> = iter.clone().rev().collect();
+        assert_eq!(rest, [values[6], values[5], values[4]]);
+
+        assert_eq!(iter.nth(3), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    #[test]
+    fn test_iter_u32_f64() {
+        let mut rng = Mx3Rng::new(4);
+        let mut expected = rng.clone();
+
+        for value in rng.iter_u32().take(100) {
+            assert_eq!(value, expected.next_u32());
+        }
+
+        for value in rng.iter_f64().take(100) {
+            assert_eq!(value, expected.gen_f64());
+        }
+
+        assert!(rng
+            .iter_f64()
+            .take(1000)
+            .all(|value| (0.0..1.0).contains(&value)));
+    }
+}
@@ -4,6 +4,7 @@
 //! The crate is *not* intended for cryptographically secure purposes.
 //!
 //! The crate implements versions 1, 2, and 3.
+//! The version can also be selected at runtime using [`Version`].
 //!
 //! ## Examples
 //!
@@ -30,15 +31,52 @@
 //! let hash_digest = mx3::v3::hash(b"Hello world!", 123456789);
 //! println!("{:x}", hash_digest);
 //! ```
+//!
+//! ## Stability
+//!
+//! The outputs of [`chunking`], [`permutation`], [`sharding`], and [`sketch`],
+//! including serialized forms, are stable between versions of this crate.
+//!
+//! The sampling methods of the `Mx3Rng` generators, such as
+//! [`v3::Mx3Rng::gen_range_u64()`], and the samples of `AliasTable` are
+//! deterministic for the generator's state and only change in a new major
+//! version of this crate.
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 #![no_std]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(any(feature = "std", test))]
+extern crate std;
+
 pub mod v1;
 pub mod v2;
 pub mod v3;
 
+pub mod chunking;
+pub mod permutation;
+pub mod sharding;
+
+pub mod sketch;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+#[cfg(feature = "stats")]
+pub mod stats;
+
+mod distributions;
+mod iter;
+mod version;
+
+pub use iter::{BoundedRngIter, RngIter};
+pub use version::*;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[cfg(feature = "alloc")]
+pub use distributions::AliasTable;
+
 #[cfg_attr(docsrs, doc(cfg(feature = "hasher")))]
 #[cfg(any(feature = "hasher", doc))]
 mod hasher;
@@ -0,0 +1,452 @@
+//! Keyed permutations of integer ranges.
+//!
+//! [`crate::v3::mix()`] is a bijection only over all of `u64`. [`Permutation`]
+//! shuffles the integers in `0..n` for any `n` up to 2<sup>64</sup>, such as
+//! for assigning non-sequential identifiers within a fixed range.
+//!
+//! The permutations use version 3 of the algorithm.
+//! Their outputs are stable, as described in the
+//! [crate documentation](crate#stability).
+//!
+//! ```rust
+//! use mx3::permutation::Permutation;
+//!
+//! // Six digit invoice numbers that do not reveal the order of the invoices.
+//! let permutation = Permutation::new(900_000, 0x1234_5678);
+//!
+//! let invoice_number = 100_000 + permutation.forward(42);
+//! assert!((100_000..1_000_000).contains(&invoice_number));
+//! assert_eq!(permutation.inverse(invoice_number - 100_000), 42);
+//! ```
+//!
+//! [`Permutation::iter()`] visits the range in shuffled order without
+//! storing the indices, such as for shuffling a large dataset in each epoch
+//! of training:
+//!
+//! ```rust
+//! use mx3::permutation::Permutation;
+//!
+//! let dataset_len = 1_000_000_000;
+//! let seed = 123;
+//!
+//! for epoch in 0..2 {
+//!     let permutation = Permutation::new(dataset_len, mx3::v3::mix_keyed(epoch, seed));
+//!
+//!     for index in permutation.iter().take(3) {
+//!         assert!(index < dataset_len);
+//!     }
+//! }
+//! ```
+//!
+//! The permutations are *not* cryptographically secure.
+use core::iter::FusedIterator;
+
+use crate::v3::mix;
+
+const ROUNDS: usize = 8;
+
+/// Keyed permutation of the integers in `0..n`.
+///
+/// The permutation is a balanced Feistel network over the smallest even
+/// number of bits that fits `n - 1`, with round functions keyed by
+/// [`crate::v3::mix()`]. Outputs outside the range are encrypted again
+/// (cycle walking) until they are in the range, which takes fewer than 4
+/// rounds of the network on average.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Permutation {
+    max: u64,
+    key: u64,
+    half_bits: u32,
+    round_keys: [u64; ROUNDS],
+}
+
+impl Permutation {
+    /// Creates the permutation of `0..n` for the given key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    pub fn new(n: u64, key: u64) -> Self {
+        assert!(n > 0, "n must not be 0");
+
+        Self::with_max(n - 1, key)
+    }
+
+    /// Creates the permutation of all the `u64` values for the given key.
+    pub fn full(key: u64) -> Self {
+        Self::with_max(u64::MAX, key)
+    }
+
+    fn with_max(max: u64, key: u64) -> Self {
+        let bits = 64 - max.leading_zeros();
+        let half_bits = bits.div_ceil(2).max(1);
+        let mut round_keys = [0u64; ROUNDS];
+
+        for (index, round_key) in round_keys.iter_mut().enumerate() {
+            *round_key = mix(key ^ mix(index as u64 + 1));
+        }
+
+        Self {
+            max,
+            key,
+            half_bits,
+            round_keys,
+        }
+    }
+
+    /// Returns the number of integers in the range, which is `n`.
+    pub fn size(&self) -> u128 {
+        self.max as u128 + 1
+    }
+
+    /// Returns the largest integer in the range, which is `n - 1`.
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// Returns the key.
+    pub fn key(&self) -> u64 {
+        self.key
+    }
+
+    /// Returns the position of `i` in the permutation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is not in the range.
+    pub fn forward(&self, i: u64) -> u64 {
+        assert!(i <= self.max, "i must be less than n");
+
+        let mut x = self.encrypt(i);
+
+        while x > self.max {
+            x = self.encrypt(x);
+        }
+
+        x
+    }
+
+    /// Reverses [`Self::forward()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is not in the range.
+    pub fn inverse(&self, i: u64) -> u64 {
+        assert!(i <= self.max, "i must be less than n");
+
+        let mut x = self.decrypt(i);
+
+        while x > self.max {
+            x = self.decrypt(x);
+        }
+
+        x
+    }
+
+    /// Returns an iterator over the range in permuted order.
+    ///
+    /// The iterator yields [`Self::forward()`] of each position in the range
+    /// in O(1) memory. It can skip to any position in O(1) time using
+    /// [`Iterator::nth()`] and can iterate from the back.
+    pub fn iter(&self) -> Iter<'_> {
+        self.iter_from(0)
+    }
+
+    /// Returns an iterator like [`Self::iter()`] starting at the given
+    /// position.
+    ///
+    /// This is useful for resuming an iteration using the position
+    /// returned by [`Iter::position()`]. If the position is outside the
+    /// range, the iterator is empty.
+    pub fn iter_from(&self, position: u64) -> Iter<'_> {
+        Iter {
+            permutation: self,
+            front: position,
+            back: self.max,
+            done: position > self.max,
+        }
+    }
+
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.half_bits)
+    }
+
+    fn round(&self, index: usize, x: u64) -> u64 {
+        mix(x ^ self.round_keys[index]) & self.mask()
+    }
+
+    fn encrypt(&self, x: u64) -> u64 {
+        let mut left = x >> self.half_bits;
+        let mut right = x & self.mask();
+
+        for index in 0..ROUNDS {
+            (left, right) = (right, left ^ self.round(index, right));
+        }
+
+        left << self.half_bits | right
+    }
+
+    fn decrypt(&self, x: u64) -> u64 {
+        let mut left = x >> self.half_bits;
+        let mut right = x & self.mask();
+
+        for index in (0..ROUNDS).rev() {
+            (left, right) = (right ^ self.round(index, left), left);
+        }
+
+        left << self.half_bits | right
+    }
+}
+
+impl<'a> IntoIterator for &'a Permutation {
+    type Item = u64;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator returned by [`Permutation::iter()`].
+#[derive(Clone, Debug)]
+pub struct Iter<'a> {
+    permutation: &'a Permutation,
+    front: u64,
+    back: u64,
+    done: bool,
+}
+
+impl Iter<'_> {
+    /// Returns the position in the range of the next index from the front,
+    /// or `None` if the iterator is exhausted.
+    pub fn position(&self) -> Option<u64> {
+        if self.done {
+            None
+        } else {
+            Some(self.front)
+        }
+    }
+
+    /// Returns the number of remaining indices.
+    pub fn remaining(&self) -> u128 {
+        if self.done {
+            0
+        } else {
+            (self.back - self.front) as u128 + 1
+        }
+    }
+}
+
+impl Iterator for Iter<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let position = self.front;
+
+        if self.front == self.back {
+            self.done = true;
+        } else {
+            self.front += 1;
+        }
+
+        Some(self.permutation.forward(position))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.remaining()) {
+            Ok(remaining) => (remaining, Some(remaining)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if self.remaining() <= n as u128 {
+            self.done = true;
+            return None;
+        }
+
+        self.front += n as u64;
+        self.next()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let position = self.back;
+
+        if self.front == self.back {
+            self.done = true;
+        } else {
+            self.back -= 1;
+        }
+
+        Some(self.permutation.forward(position))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        if self.remaining() <= n as u128 {
+            self.done = true;
+            return None;
+        }
+
+        self.back -= n as u64;
+        self.next_back()
+    }
+}
+
+impl FusedIterator for Iter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_ranges() {
+        for n in [1, 2, 3, 4, 5, 10, 255, 256, 257, 1000] {
+            let permutation = Permutation::new(n, 123);
+            let mut seen = std::vec![false; n as usize];
+
+            for i in 0..n {
+                let x = permutation.forward(i);
+                assert!(x < n);
+                assert!(!seen[x as usize], "{} {}", n, i);
+                seen[x as usize] = true;
+
+                assert_eq!(permutation.inverse(x), i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_ranges() {
+        let permutations = [
+            Permutation::new(1 << 40, 1),
+            Permutation::new((1 << 63) + 12345, 2),
+            Permutation::new(u64::MAX, 3),
+            Permutation::full(4),
+        ];
+
+        for permutation in permutations {
+            for i in (0..1000u64).chain([permutation.max() - 1, permutation.max()]) {
+                let x = permutation.forward(i);
+                assert!(x <= permutation.max());
+                assert_eq!(permutation.inverse(x), i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_size() {
+        assert_eq!(Permutation::new(10, 0).size(), 10);
+        assert_eq!(Permutation::new(10, 0).max(), 9);
+        assert_eq!(Permutation::full(0).size(), 1 << 64);
+    }
+
+    #[test]
+    fn test_keys_differ() {
+        let a = Permutation::new(1000, 1);
+        let b = Permutation::new(1000, 2);
+        let same = (0..1000).filter(|&i| a.forward(i) == b.forward(i)).count();
+
+        assert!(same < 10, "{}", same);
+    }
+
+    #[test]
+    fn test_shuffled() {
+        let permutation = Permutation::new(1000, 5);
+        let fixed_points = (0..1000).filter(|&i| permutation.forward(i) == i).count();
+
+        assert!(fixed_points < 10, "{}", fixed_points);
+    }
+
+    #[test]
+    fn test_stable() {
+        let permutation = Permutation::new(1_000_000, 123456789);
+
+        assert_eq!(permutation.forward(0), 801753);
+        assert_eq!(permutation.forward(999_999), 874951);
+        assert_eq!(Permutation::full(123456789).forward(0), 1530830606064924408);
+    }
+
+    #[test]
+    fn test_iter() {
+        let permutation = Permutation::new(100, 1);
+        let indices: std::vec::Vec<u64> = permutation.iter().collect();
+
+        assert_eq!(indices.len(), 100);
+        assert_eq!(permutation.iter().size_hint(), (100, Some(100)));
+
+        for (position, &index) in indices.iter().enumerate() {
+            assert_eq!(index, permutation.forward(position as u64));
+        }
+
+        let mut reversed: std::vec::Vec<u64> = (&permutation).into_iter().rev().collect();
+        reversed.reverse();
+        assert_eq!(reversed, indices);
+    }
+
+    #[test]
+    fn test_iter_seek() {
+        let permutation = Permutation::new(100, 2);
+        let mut iter = permutation.iter();
+
+        assert_eq!(iter.nth(10), Some(permutation.forward(10)));
+        assert_eq!(iter.position(), Some(11));
+        assert_eq!(iter.nth_back(9), Some(permutation.forward(90)));
+        assert_eq!(iter.remaining(), 79);
+        assert_eq!(iter.nth(78), Some(permutation.forward(89)));
+        assert_eq!(iter.position(), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        let mut iter = permutation.iter();
+        assert_eq!(iter.nth(100), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_iter_resume() {
+        let permutation = Permutation::new(1000, 3);
+        let mut iter = permutation.iter();
+        iter.nth(499);
+
+        let resumed = permutation.iter_from(iter.position().unwrap());
+        assert!(resumed.eq(iter));
+
+        assert_eq!(permutation.iter_from(1000).next(), None);
+    }
+
+    #[test]
+    fn test_iter_full() {
+        let permutation = Permutation::full(4);
+        let mut iter = permutation.iter();
+
+        assert_eq!(iter.remaining(), 1 << 64);
+        assert_eq!(iter.size_hint().1, None);
+        assert_eq!(iter.next_back(), Some(permutation.forward(u64::MAX)));
+        assert_eq!(
+            iter.nth(usize::MAX - 1),
+            Some(permutation.forward(usize::MAX as u64 - 1))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_out_of_range() {
+        Permutation::new(10, 0).forward(10);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_empty() {
+        Permutation::new(0, 0);
+    }
+}
@@ -0,0 +1,223 @@
+//! Consistent hashing for distributing keys across buckets or nodes.
+//!
+//! The functions in this module use version 3 of the algorithm.
+//! Their outputs are stable, as described in the
+//! [crate documentation](crate#stability).
+//!
+//! ```rust
+//! use mx3::sharding;
+//!
+//! let key = mx3::v3::hash(b"user:123", 0);
+//!
+//! let bucket = sharding::jump(key, 10);
+//! assert!(bucket < 10);
+//!
+//! let nodes = [0xa, 0xb, 0xc];
+//! let node_index = sharding::rendezvous(key, &nodes).unwrap();
+//! assert!(node_index < nodes.len());
+//! ```
+//!
+//! With the `alloc` feature, [`HashRing`] provides a consistent hash ring
+//! for nodes of different capacities.
+use rand_core::RngCore;
+
+use crate::v3::{mix, Mx3Rng};
+
+#[cfg(feature = "alloc")]
+mod ring;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[cfg(feature = "alloc")]
+pub use ring::HashRing;
+
+/// Returns the bucket in `0..buckets` of the key using jump consistent hash.
+///
+/// This is the algorithm described in "A Fast, Minimal Memory, Consistent
+/// Hash Algorithm" by Lamping and Veach with the linear congruential
+/// generator replaced by [`Mx3Rng`] seeded with the key.
+///
+/// When the number of buckets increases from n to n + 1, only about 1/(n + 1)
+/// of the keys move, all of them to the new bucket.
+///
+/// Keys should already be well distributed, such as an output of
+/// [`crate::v3::hash()`].
+///
+/// # Panics
+///
+/// Panics if `buckets` is 0.
+pub fn jump(key: u64, buckets: u32) -> u32 {
+    assert!(buckets > 0, "buckets must not be 0");
+
+    let mut rng = Mx3Rng::new(key);
+    let mut bucket: i64 = -1;
+    let mut next: i64 = 0;
+
+    while next < buckets as i64 {
+        bucket = next;
+
+        // The upper 31 bits give a random number in (0, 1] when divided.
+        let random = ((rng.next_u64() >> 33) + 1) as f64 / (1u64 << 31) as f64;
+        next = ((bucket + 1) as f64 / random) as i64;
+    }
+
+    bucket as u32
+}
+
+/// Returns the weight of the key for the node in rendezvous hashing.
+pub fn rendezvous_weight(key: u64, node: u64) -> u64 {
+    mix(key ^ mix(node))
+}
+
+/// Returns the index of the node with the highest weight for the key using
+/// rendezvous (highest random weight) hashing.
+///
+/// Nodes are identified by an integer, such as the [`crate::v3::hash()`]
+/// of their name. Unlike [`jump()`], nodes may be removed from anywhere in
+/// the slice: only the keys assigned to the removed node move.
+///
+/// Returns `None` if there are no nodes.
+pub fn rendezvous(key: u64, nodes: &[u64]) -> Option<usize> {
+    nodes
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, &node)| (rendezvous_weight(key, node), node))
+        .map(|(index, _)| index)
+}
+
+/// Writes the indices of the nodes for the key into `output` in order of
+/// decreasing weight using rendezvous hashing.
+///
+/// This is useful for choosing replicas: the first index is the same as
+/// returned by [`rendezvous()`]. Returns the number of indices written which
+/// is the smaller of the lengths of `nodes` and `output`.
+pub fn rendezvous_ranked(key: u64, nodes: &[u64], output: &mut [usize]) -> usize {
+    let count = nodes.len().min(output.len());
+    let weight = |index: usize| (rendezvous_weight(key, nodes[index]), nodes[index]);
+
+    // Partial selection sort keeping the `count` highest weights.
+    for position in 0..count {
+        let mut best: Option<usize> = None;
+
+        for index in 0..nodes.len() {
+            if output[0..position].contains(&index) {
+                continue;
+            }
+
+            if best.is_none_or(|best| weight(index) > weight(best)) {
+                best = Some(index);
+            }
+        }
+
+        output[position] = best.unwrap();
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jump_range() {
+        for key in 0..1000 {
+            assert_eq!(jump(key, 1), 0);
+            assert!(jump(key, 7) < 7);
+        }
+    }
+
+    #[test]
+    fn test_jump_stable() {
+        assert_eq!(jump(0, 100), jump(0, 100));
+        assert_eq!(jump(123456789, 1000), 90);
+    }
+
+    #[test]
+    fn test_jump_moves_to_new_bucket() {
+        let mut moved = 0;
+
+        for key in 0..10000u64 {
+            let key = mix(key);
+            let before = jump(key, 10);
+            let after = jump(key, 11);
+
+            if before != after {
+                assert_eq!(after, 10);
+                moved += 1;
+            }
+        }
+
+        // About 1/11 of the keys should move.
+        assert!((700..1100).contains(&moved), "{}", moved);
+    }
+
+    #[test]
+    fn test_jump_balanced() {
+        let mut counts = [0u32; 8];
+
+        for key in 0..8000u64 {
+            counts[jump(mix(key), 8) as usize] += 1;
+        }
+
+        for count in counts {
+            assert!((850..1150).contains(&count), "{:?}", counts);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_jump_zero_buckets() {
+        jump(1, 0);
+    }
+
+    #[test]
+    fn test_rendezvous() {
+        assert_eq!(rendezvous(1, &[]), None);
+        assert_eq!(rendezvous(1, &[5]), Some(0));
+
+        let nodes = [10, 20, 30, 40, 50];
+        let mut counts = [0u32; 5];
+
+        for key in 0..5000u64 {
+            counts[rendezvous(key, &nodes).unwrap()] += 1;
+        }
+
+        for count in counts {
+            assert!((850..1150).contains(&count), "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn test_rendezvous_remove_node() {
+        let nodes = [10, 20, 30, 40, 50];
+        let removed = [10, 20, 40, 50];
+
+        for key in 0..1000u64 {
+            let before = nodes[rendezvous(key, &nodes).unwrap()];
+            let after = removed[rendezvous(key, &removed).unwrap()];
+
+            if before != 30 {
+                assert_eq!(before, after);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rendezvous_ranked() {
+        let nodes = [10, 20, 30, 40, 50];
+
+        for key in 0..100u64 {
+            let mut output = [0usize; 3];
+            assert_eq!(rendezvous_ranked(key, &nodes, &mut output), 3);
+            assert_eq!(output[0], rendezvous(key, &nodes).unwrap());
+            assert_ne!(output[0], output[1]);
+            assert_ne!(output[1], output[2]);
+            assert!(
+                rendezvous_weight(key, nodes[output[0]]) > rendezvous_weight(key, nodes[output[1]])
+            );
+        }
+
+        let mut output = [0usize; 8];
+        assert_eq!(rendezvous_ranked(1, &nodes, &mut output), 5);
+    }
+}
@@ -0,0 +1,348 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::v3::hash;
+
+const DEFAULT_VIRTUAL_NODES: u32 = 100;
+
+/// Consistent hash ring with weighted virtual nodes.
+///
+/// Each node is placed on the ring at a number of positions (virtual nodes)
+/// proportional to its weight. A key belongs to the first node found walking
+/// the ring clockwise from the position of the key. When a node is added or
+/// removed, only the keys between its positions and the preceding positions
+/// move.
+///
+/// Nodes are identified by their bytes, such as a name or address.
+/// Positions are computed using [`crate::v3::hash()`] and are
+/// [stable](crate#stability).
+///
+/// ```rust
+/// use mx3::sharding::HashRing;
+///
+/// let mut ring = HashRing::new();
+/// ring.add("node-a", 1);
+/// ring.add("node-b", 1);
+/// ring.add("node-c", 2);
+///
+/// let node = ring.get(b"user:123").unwrap();
+/// let replicas = ring.replicas(b"user:123", 2);
+/// assert_eq!(replicas[0], node);
+/// ```
+#[derive(Clone, Debug)]
+pub struct HashRing<N> {
+    virtual_nodes: u32,
+    nodes: Vec<(N, u32)>,
+    points: Vec<(u64, usize)>,
+}
+
+impl<N: AsRef<[u8]>> HashRing<N> {
+    /// Creates an empty ring with 100 virtual nodes per unit of weight.
+    pub fn new() -> Self {
+        Self::with_virtual_nodes(DEFAULT_VIRTUAL_NODES)
+    }
+
+    /// Creates an empty ring with the given number of virtual nodes per unit
+    /// of weight.
+    ///
+    /// More virtual nodes distribute keys more evenly at the cost of memory
+    /// and slower changes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `virtual_nodes` is 0.
+    pub fn with_virtual_nodes(virtual_nodes: u32) -> Self {
+        assert!(virtual_nodes > 0, "virtual_nodes must not be 0");
+
+        Self {
+            virtual_nodes,
+            nodes: Vec::new(),
+            points: Vec::new(),
+        }
+    }
+
+    /// Returns the number of nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns whether the ring has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns an iterator of the nodes and their weights.
+    pub fn nodes(&self) -> impl Iterator<Item = (&N, u32)> {
+        self.nodes.iter().map(|(node, weight)| (node, *weight))
+    }
+
+    /// Adds the node with the given weight.
+    ///
+    /// If a node with the same bytes is already in the ring, it is replaced
+    /// and the previous node is returned. A weight of 0 keeps the node in the
+    /// ring without assigning it any keys.
+    pub fn add(&mut self, node: N, weight: u32) -> Option<N> {
+        let previous = self.remove(node.as_ref());
+
+        let index = self.nodes.len();
+        let bytes = node.as_ref();
+        let count = weight as u64 * self.virtual_nodes as u64;
+
+        self.points.reserve(count as usize);
+
+        for replica in 0..count {
+            self.points.push((hash(bytes, replica), index));
+        }
+
+        self.nodes.push((node, weight));
+        self.sort_points();
+
+        previous
+    }
+
+    /// Removes the node with the given bytes and returns it.
+    pub fn remove(&mut self, node: &[u8]) -> Option<N> {
+        let index = self
+            .nodes
+            .iter()
+            .position(|(existing, _)| existing.as_ref() == node)?;
+
+        let (removed, _) = self.nodes.remove(index);
+
+        self.points.retain(|&(_, point_index)| point_index != index);
+
+        for (_, point_index) in self.points.iter_mut() {
+            if *point_index > index {
+                *point_index -= 1;
+            }
+        }
+
+        Some(removed)
+    }
+
+    /// Returns the node for the key.
+    ///
+    /// Returns `None` if the ring has no virtual nodes.
+    pub fn get(&self, key: &[u8]) -> Option<&N> {
+        self.get_by_hash(hash(key, 0))
+    }
+
+    /// Returns the node for the already hashed key.
+    pub fn get_by_hash(&self, key_hash: u64) -> Option<&N> {
+        let start = self.start(key_hash)?;
+        let (_, index) = self.points[start];
+
+        Some(&self.nodes[index].0)
+    }
+
+    /// Returns up to `count` distinct nodes for the key, starting with the
+    /// node returned by [`Self::get()`].
+    ///
+    /// This is useful for choosing the nodes holding replicas of the key.
+    pub fn replicas(&self, key: &[u8], count: usize) -> Vec<&N> {
+        self.replicas_by_hash(hash(key, 0), count)
+    }
+
+    /// Returns up to `count` distinct nodes for the already hashed key.
+    pub fn replicas_by_hash(&self, key_hash: u64, count: usize) -> Vec<&N> {
+        let mut indices: Vec<usize> = Vec::new();
+
+        if let Some(start) = self.start(key_hash) {
+            let points = self.points[start..].iter().chain(&self.points[..start]);
+
+            for &(_, index) in points {
+                if indices.len() >= count {
+                    break;
+                }
+
+                if !indices.contains(&index) {
+                    indices.push(index);
+                }
+            }
+        }
+
+        indices
+            .into_iter()
+            .map(|index| &self.nodes[index].0)
+            .collect()
+    }
+
+    fn start(&self, key_hash: u64) -> Option<usize> {
+        if self.points.is_empty() {
+            return None;
+        }
+
+        let position = self.points.partition_point(|&(point, _)| point < key_hash);
+
+        Some(if position == self.points.len() {
+            0
+        } else {
+            position
+        })
+    }
+
+    fn sort_points(&mut self) {
+        let nodes = &self.nodes;
+
+        // Ties are broken by the node bytes so the ring does not depend on
+        // the order the nodes were added.
+        self.points.sort_unstable_by(|a, b| match a.0.cmp(&b.0) {
+            Ordering::Equal => nodes[a.1].0.as_ref().cmp(nodes[b.1].0.as_ref()),
+            ordering => ordering,
+        });
+    }
+}
+
+impl<N: AsRef<[u8]>> Default for HashRing<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::format;
+    use std::string::String;
+
+    fn ring_with_nodes(count: usize) -> HashRing<String> {
+        let mut ring = HashRing::new();
+
+        for index in 0..count {
+            ring.add(format!("node-{}", index), 1);
+        }
+
+        ring
+    }
+
+    #[test]
+    fn test_empty() {
+        let ring: HashRing<&str> = HashRing::new();
+
+        assert!(ring.is_empty());
+        assert_eq!(ring.get(b"a"), None);
+        assert!(ring.replicas(b"a", 3).is_empty());
+    }
+
+    #[test]
+    fn test_add_remove() {
+        let mut ring = HashRing::new();
+
+        assert_eq!(ring.add("a", 1), None);
+        assert_eq!(ring.add("b", 1), None);
+        assert_eq!(ring.add("a", 2), Some("a"));
+        assert_eq!(ring.len(), 2);
+        assert_eq!(ring.points.len(), 300);
+
+        assert_eq!(ring.remove(b"a"), Some("a"));
+        assert_eq!(ring.remove(b"a"), None);
+        assert_eq!(ring.len(), 1);
+        assert_eq!(ring.get(b"key"), Some(&"b"));
+    }
+
+    #[test]
+    fn test_insertion_order_independent() {
+        let mut ring1 = HashRing::new();
+        ring1.add("a", 1);
+        ring1.add("b", 1);
+
+        let mut ring2 = HashRing::new();
+        ring2.add("b", 1);
+        ring2.add("a", 1);
+
+        for index in 0..1000u32 {
+            assert_eq!(
+                ring1.get(&index.to_le_bytes()),
+                ring2.get(&index.to_le_bytes())
+            );
+        }
+    }
+
+    #[test]
+    fn test_balanced() {
+        let ring = ring_with_nodes(4);
+        let mut counts = [0u32; 4];
+
+        for index in 0..10000u32 {
+            let node = ring.get(&index.to_le_bytes()).unwrap();
+            counts[node[5..].parse::<usize>().unwrap()] += 1;
+        }
+
+        for count in counts {
+            assert!((2000..3000).contains(&count), "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn test_weighted() {
+        let mut ring = HashRing::new();
+        ring.add("light", 1);
+        ring.add("heavy", 3);
+
+        let heavy = (0..10000u32)
+            .filter(|&index| ring.get(&index.to_le_bytes()) == Some(&"heavy"))
+            .count();
+
+        assert!((7000..8000).contains(&heavy), "{}", heavy);
+    }
+
+    #[test]
+    fn test_add_node_movement() {
+        let before = ring_with_nodes(10);
+        let mut after = before.clone();
+        after.add(String::from("node-10"), 1);
+
+        let mut moved = 0;
+
+        for index in 0..10000u32 {
+            let old = before.get(&index.to_le_bytes()).unwrap();
+            let new = after.get(&index.to_le_bytes()).unwrap();
+
+            if old != new {
+                assert_eq!(new, "node-10");
+                moved += 1;
+            }
+        }
+
+        // About 1/11 of the keys should move.
+        assert!((500..1400).contains(&moved), "{}", moved);
+    }
+
+    #[test]
+    fn test_remove_node_movement() {
+        let before = ring_with_nodes(10);
+        let mut after = before.clone();
+        after.remove(b"node-3");
+
+        let mut moved = 0;
+
+        for index in 0..10000u32 {
+            let old = before.get(&index.to_le_bytes()).unwrap();
+            let new = after.get(&index.to_le_bytes()).unwrap();
+
+            if old != new {
+                assert_eq!(old, "node-3");
+                moved += 1;
+            }
+        }
+
+        assert!((500..1500).contains(&moved), "{}", moved);
+    }
+
+    #[test]
+    fn test_replicas() {
+        let ring = ring_with_nodes(5);
+
+        for index in 0..100u32 {
+            let replicas = ring.replicas(&index.to_le_bytes(), 3);
+
+            assert_eq!(replicas.len(), 3);
+            assert_eq!(replicas[0], ring.get(&index.to_le_bytes()).unwrap());
+            assert_ne!(replicas[0], replicas[1]);
+            assert_ne!(replicas[0], replicas[2]);
+            assert_ne!(replicas[1], replicas[2]);
+        }
+
+        assert_eq!(ring.replicas(b"a", 10).len(), 5);
+    }
+}
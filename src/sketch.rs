@@ -0,0 +1,153 @@
+//! Probabilistic data structures for summarizing large sets and streams.
+//!
+//! The structures in this module use version 3 of the algorithm.
+//! Their outputs and serialized forms are stable, as described in the
+//! [crate documentation](crate#stability).
+//!
+//! [`MinHash`] and [`SimHash`] compute signatures for estimating the
+//! similarity of documents. With the `alloc` feature, [`BloomFilter`] tests
+//! set membership, [`Hll`] estimates the number of distinct items, and
+//! [`CountMinSketch`] and [`TopK`] estimate the frequencies of items.
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+#[cfg(feature = "alloc")]
+mod bloom;
+#[cfg(feature = "alloc")]
+mod count_min;
+#[cfg(feature = "alloc")]
+mod hll;
+mod minhash;
+mod simhash;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[cfg(feature = "alloc")]
+pub use bloom::BloomFilter;
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[cfg(feature = "alloc")]
+pub use count_min::{CountMinSketch, TopK};
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[cfg(feature = "alloc")]
+pub use hll::Hll;
+pub use minhash::{jaccard, MinHash};
+pub use simhash::{hamming, SimHash};
+
+/// Error returned by the structures in this module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The structures cannot be combined because their parameters differ.
+    Incompatible,
+    /// The serialized form is invalid or of a different structure.
+    InvalidEncoding,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Incompatible => f.write_str("incompatible parameters"),
+            Self::InvalidEncoding => f.write_str("invalid encoding"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Writes the serialized form of a structure in little-endian byte order.
+#[cfg(feature = "alloc")]
+struct Encoder {
+    buf: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+impl Encoder {
+    fn new(magic: &[u8; 4]) -> Self {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(magic);
+
+        Self { buf }
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_bytes(&mut self, value: &[u8]) {
+        self.buf.extend_from_slice(value);
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+/// Reads the serialized form written by [`Encoder`].
+#[cfg(feature = "alloc")]
+struct Decoder<'a> {
+    remain: &'a [u8],
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Decoder<'a> {
+    fn new(buf: &'a [u8], magic: &[u8; 4]) -> Result<Self, Error> {
+        let mut decoder = Self { remain: buf };
+
+        if decoder.read(4)? != magic {
+            return Err(Error::InvalidEncoding);
+        }
+
+        Ok(decoder)
+    }
+
+    fn read(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.remain.len() < len {
+            return Err(Error::InvalidEncoding);
+        }
+
+        let (left, right) = self.remain.split_at(len);
+        self.remain = right;
+
+        Ok(left)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.read(1)?[0])
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Error> {
+        let mut int_buf = [0u8; 4];
+        int_buf.copy_from_slice(self.read(4)?);
+
+        Ok(u32::from_le_bytes(int_buf))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, Error> {
+        let mut int_buf = [0u8; 8];
+        int_buf.copy_from_slice(self.read(8)?);
+
+        Ok(u64::from_le_bytes(int_buf))
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        if self.remain.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidEncoding)
+        }
+    }
+}
+
+/// Returns the bytes of a distinct item for the tests of the sketches.
+#[cfg(all(test, feature = "alloc"))]
+fn item(index: u32) -> [u8; 4] {
+    index.to_le_bytes()
+}
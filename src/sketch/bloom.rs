@@ -0,0 +1,380 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::LN_2;
+
+use super::{Decoder, Encoder, Error};
+use crate::v3::hash;
+
+const MAGIC: &[u8; 4] = b"MX3B";
+const ENCODING_VERSION: u8 = 1;
+
+/// Bloom filter for testing whether an item is in a set.
+///
+/// Items that were inserted are always reported as present. Items that were
+/// not inserted are reported as present with a probability given by the
+/// false positive rate.
+///
+/// The bit indices of an item are derived from two seeded
+/// [`crate::v3::hash()`] calls using enhanced double hashing as described in
+/// "Bloom Filters in Probabilistic Verification" by Dillinger and Manolios.
+///
+/// ```rust
+/// use mx3::sketch::BloomFilter;
+///
+/// let mut filter = BloomFilter::with_rate(1000, 0.01);
+/// filter.insert(b"apple");
+///
+/// assert!(filter.contains(b"apple"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+    seed: u64,
+}
+
+impl BloomFilter {
+    /// Creates an empty filter with the given number of bits and hash
+    /// functions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_bits` or `num_hashes` is 0.
+    pub fn new(num_bits: u64, num_hashes: u32) -> Self {
+        Self::with_seed(num_bits, num_hashes, 0)
+    }
+
+    /// Creates an empty filter like [`Self::new()`] with the given hash seed.
+    ///
+    /// Filters can only be combined if they have the same seed.
+    pub fn with_seed(num_bits: u64, num_hashes: u32, seed: u64) -> Self {
+        assert!(num_bits > 0, "num_bits must not be 0");
+        assert!(num_hashes > 0, "num_hashes must not be 0");
+
+        let num_words = usize::try_from(num_bits.div_ceil(64)).expect("num_bits is too large");
+
+        Self {
+            bits: vec![0; num_words],
+            num_bits,
+            num_hashes,
+            seed,
+        }
+    }
+
+    /// Creates an empty filter sized for the expected number of items and
+    /// false positive rate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `false_positive_rate` is not between 0 and 1 exclusive.
+    pub fn with_rate(expected_items: u64, false_positive_rate: f64) -> Self {
+        assert!(
+            false_positive_rate > 0.0 && false_positive_rate < 1.0,
+            "false_positive_rate must be between 0 and 1 exclusive"
+        );
+
+        let items = expected_items.max(1) as f64;
+        let num_bits = libm::ceil(-items * libm::log(false_positive_rate) / (LN_2 * LN_2));
+        let num_hashes = libm::round(num_bits / items * LN_2);
+
+        Self::new(num_bits.max(1.0) as u64, num_hashes.max(1.0) as u32)
+    }
+
+    /// Returns the number of bits.
+    pub fn num_bits(&self) -> u64 {
+        self.num_bits
+    }
+
+    /// Returns the number of hash functions.
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    /// Returns the hash seed.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the number of bits that are set.
+    pub fn count_ones(&self) -> u64 {
+        self.bits.iter().map(|word| word.count_ones() as u64).sum()
+    }
+
+    /// Returns whether no items were inserted.
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|&word| word == 0)
+    }
+
+    /// Returns the estimated false positive rate given the bits that are set.
+    pub fn estimated_false_positive_rate(&self) -> f64 {
+        let fraction = self.count_ones() as f64 / self.num_bits as f64;
+
+        libm::pow(fraction, self.num_hashes as f64)
+    }
+
+    /// Inserts the item.
+    pub fn insert(&mut self, item: &[u8]) {
+        for index in self.indices(item) {
+            self.bits[(index / 64) as usize] |= 1 << (index % 64);
+        }
+    }
+
+    /// Returns whether the item may have been inserted.
+    pub fn contains(&self, item: &[u8]) -> bool {
+        self.indices(item)
+            .all(|index| self.bits[(index / 64) as usize] & (1 << (index % 64)) != 0)
+    }
+
+    /// Removes all the items.
+    pub fn clear(&mut self) {
+        self.bits.fill(0);
+    }
+
+    /// Adds the items of the other filter to this filter.
+    ///
+    /// The result is the same as if the items were inserted into this filter.
+    ///
+    /// Returns an error if the filters have different parameters.
+    pub fn union(&mut self, other: &Self) -> Result<(), Error> {
+        self.check_compatible(other)?;
+
+        for (word, other_word) in self.bits.iter_mut().zip(&other.bits) {
+            *word |= other_word;
+        }
+
+        Ok(())
+    }
+
+    /// Removes the items not in the other filter from this filter.
+    ///
+    /// The result has a false positive rate at least as high as a filter
+    /// with only the items in both sets inserted.
+    ///
+    /// Returns an error if the filters have different parameters.
+    pub fn intersect(&mut self, other: &Self) -> Result<(), Error> {
+        self.check_compatible(other)?;
+
+        for (word, other_word) in self.bits.iter_mut().zip(&other.bits) {
+            *word &= other_word;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the serialized form of the filter.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(MAGIC);
+        encoder.write_u8(ENCODING_VERSION);
+        encoder.write_u32(self.num_hashes);
+        encoder.write_u64(self.seed);
+        encoder.write_u64(self.num_bits);
+
+        for &word in &self.bits {
+            encoder.write_u64(word);
+        }
+
+        encoder.finish()
+    }
+
+    /// Creates the filter from the serialized form returned by
+    /// [`Self::to_bytes()`].
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, Error> {
+        let mut decoder = Decoder::new(buf, MAGIC)?;
+
+        if decoder.read_u8()? != ENCODING_VERSION {
+            return Err(Error::InvalidEncoding);
+        }
+
+        let num_hashes = decoder.read_u32()?;
+        let seed = decoder.read_u64()?;
+        let num_bits = decoder.read_u64()?;
+
+        if num_hashes == 0 || num_bits == 0 {
+            return Err(Error::InvalidEncoding);
+        }
+
+        let num_words = num_bits.div_ceil(64);
+        let mut bits = Vec::new();
+
+        for _ in 0..num_words {
+            bits.push(decoder.read_u64()?);
+        }
+
+        decoder.finish()?;
+
+        // The unused bits of the last word must be clear, otherwise they
+        // would be counted by the estimates.
+        let used_bits = num_bits % 64;
+
+        if used_bits != 0 && bits[bits.len() - 1] >> used_bits != 0 {
+            return Err(Error::InvalidEncoding);
+        }
+
+        Ok(Self {
+            bits,
+            num_bits,
+            num_hashes,
+            seed,
+        })
+    }
+
+    fn check_compatible(&self, other: &Self) -> Result<(), Error> {
+        if self.num_bits == other.num_bits
+            && self.num_hashes == other.num_hashes
+            && self.seed == other.seed
+        {
+            Ok(())
+        } else {
+            Err(Error::Incompatible)
+        }
+    }
+
+    fn indices(&self, item: &[u8]) -> impl Iterator<Item = u64> {
+        let num_bits = self.num_bits;
+        let mut x = hash(item, self.seed) % num_bits;
+        let mut y = hash(item, !self.seed) % num_bits;
+
+        (0..self.num_hashes as u64).map(move |round| {
+            let index = x;
+            x = add_mod(x, y, num_bits);
+            y = add_mod(y, round + 1, num_bits);
+            index
+        })
+    }
+}
+
+fn add_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    ((a as u128 + b as u128) % modulus as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sketch::item;
+
+    #[test]
+    fn test_insert_contains() {
+        let mut filter = BloomFilter::new(1000, 5);
+        assert!(filter.is_empty());
+        assert!(!filter.contains(b"apple"));
+
+        filter.insert(b"apple");
+
+        assert!(!filter.is_empty());
+        assert!(filter.contains(b"apple"));
+        assert!(!filter.contains(b"banana"));
+        assert!(filter.count_ones() <= 5);
+
+        filter.clear();
+        assert!(!filter.contains(b"apple"));
+    }
+
+    #[test]
+    fn test_with_rate() {
+        let filter = BloomFilter::with_rate(1000, 0.01);
+
+        assert_eq!(filter.num_bits(), 9586);
+        assert_eq!(filter.num_hashes(), 7);
+    }
+
+    #[test]
+    fn test_false_positive_rate() {
+        let mut filter = BloomFilter::with_rate(10000, 0.01);
+
+        for index in 0..10000 {
+            filter.insert(&item(index));
+        }
+
+        for index in 0..10000 {
+            assert!(filter.contains(&item(index)));
+        }
+
+        let false_positives = (10000..110000)
+            .filter(|&index| filter.contains(&item(index)))
+            .count();
+
+        assert!(
+            (700..1300).contains(&false_positives),
+            "{}",
+            false_positives
+        );
+
+        let estimate = filter.estimated_false_positive_rate();
+        assert!((0.007..0.013).contains(&estimate), "{}", estimate);
+    }
+
+    #[test]
+    fn test_union_intersect() {
+        let mut filter1 = BloomFilter::new(4096, 4);
+        let mut filter2 = BloomFilter::new(4096, 4);
+
+        filter1.insert(b"a");
+        filter1.insert(b"both");
+        filter2.insert(b"b");
+        filter2.insert(b"both");
+
+        let mut union = filter1.clone();
+        union.union(&filter2).unwrap();
+        assert!(union.contains(b"a"));
+        assert!(union.contains(b"b"));
+        assert!(union.contains(b"both"));
+
+        let mut intersection = filter1.clone();
+        intersection.intersect(&filter2).unwrap();
+        assert!(intersection.contains(b"both"));
+        assert!(!intersection.contains(b"a"));
+        assert!(!intersection.contains(b"b"));
+    }
+
+    #[test]
+    fn test_incompatible() {
+        let mut filter = BloomFilter::new(4096, 4);
+
+        assert_eq!(
+            filter.union(&BloomFilter::new(4096, 5)),
+            Err(Error::Incompatible)
+        );
+        assert_eq!(
+            filter.intersect(&BloomFilter::with_seed(4096, 4, 1)),
+            Err(Error::Incompatible)
+        );
+    }
+
+    #[test]
+    fn test_serialization() {
+        let mut filter = BloomFilter::with_seed(1000, 3, 123);
+        filter.insert(b"apple");
+
+        let bytes = filter.to_bytes();
+        assert_eq!(bytes.len(), 4 + 1 + 4 + 8 + 8 + 16 * 8);
+
+        let decoded = BloomFilter::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, filter);
+        assert!(decoded.contains(b"apple"));
+
+        assert_eq!(
+            BloomFilter::from_bytes(&bytes[0..bytes.len() - 1]),
+            Err(Error::InvalidEncoding)
+        );
+        assert_eq!(
+            BloomFilter::from_bytes(b"MX3H"),
+            Err(Error::InvalidEncoding)
+        );
+
+        let mut padded = filter.clone();
+        padded.bits[15] |= 1 << 63;
+        assert_eq!(
+            BloomFilter::from_bytes(&padded.to_bytes()),
+            Err(Error::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn test_stable() {
+        let mut filter = BloomFilter::new(64, 2);
+        filter.insert(b"apple");
+
+        assert_eq!(filter.bits[0], 0x0000_0000_0020_0002);
+    }
+}
@@ -0,0 +1,592 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::E;
+
+use super::{Decoder, Encoder, Error};
+use crate::v3::hash;
+
+const SKETCH_MAGIC: &[u8; 4] = b"MX3C";
+const TOP_K_MAGIC: &[u8; 4] = b"MX3K";
+const ENCODING_VERSION: u8 = 1;
+
+/// Count-Min sketch for estimating the frequencies of items.
+///
+/// The sketch is a table of counters with `depth` rows of `width` columns.
+/// The column of an item in each row is given by [`crate::v3::hash()`]
+/// seeded with the row. The estimate of an item is the minimum of its
+/// counters, so it is never less than the true count.
+///
+/// Counts are added using conservative update which increments only the
+/// counters needed to keep the estimate correct, reducing the
+/// overestimation.
+///
+/// Sketches with the same parameters can be merged, such as when they count
+/// different shards of a stream.
+///
+/// ```rust
+/// use mx3::sketch::CountMinSketch;
+///
+/// let mut sketch = CountMinSketch::with_error(0.001, 0.01);
+/// sketch.add(b"GET /index.html", 3);
+/// sketch.insert(b"GET /about.html");
+///
+/// assert!(sketch.estimate(b"GET /index.html") >= 3);
+/// assert_eq!(sketch.total(), 4);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CountMinSketch {
+    counters: Vec<u64>,
+    width: usize,
+    depth: usize,
+    seed: u64,
+    total: u64,
+}
+
+impl CountMinSketch {
+    /// Creates an empty sketch with the given number of columns and rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `depth` is 0.
+    pub fn new(width: usize, depth: usize) -> Self {
+        Self::with_seed(width, depth, 0)
+    }
+
+    /// Creates an empty sketch like [`Self::new()`] with the given hash seed.
+    ///
+    /// Sketches can only be merged if they have the same seed.
+    pub fn with_seed(width: usize, depth: usize, seed: u64) -> Self {
+        assert!(width > 0, "width must not be 0");
+        assert!(depth > 0, "depth must not be 0");
+
+        let len = width.checked_mul(depth).expect("sketch is too large");
+
+        Self {
+            counters: vec![0; len],
+            width,
+            depth,
+            seed,
+            total: 0,
+        }
+    }
+
+    /// Creates an empty sketch sized so that, with probability `1 - delta`,
+    /// an estimate exceeds the true count by at most `epsilon` times the
+    /// total count.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `epsilon` or `delta` is not between 0 and 1 exclusive.
+    pub fn with_error(epsilon: f64, delta: f64) -> Self {
+        assert!(
+            epsilon > 0.0 && epsilon < 1.0,
+            "epsilon must be between 0 and 1 exclusive"
+        );
+        assert!(
+            delta > 0.0 && delta < 1.0,
+            "delta must be between 0 and 1 exclusive"
+        );
+
+        let width = libm::ceil(E / epsilon) as usize;
+        let depth = libm::ceil(libm::log(1.0 / delta)) as usize;
+
+        Self::new(width, depth.max(1))
+    }
+
+    /// Returns the number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the hash seed.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the sum of all the counts added.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Returns whether no counts were added.
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    /// Adds 1 to the count of the item.
+    pub fn insert(&mut self, item: &[u8]) {
+        self.add(item, 1);
+    }
+
+    /// Adds to the count of the item and returns its new estimate.
+    pub fn add(&mut self, item: &[u8], count: u64) -> u64 {
+        let estimate = self.estimate(item).saturating_add(count);
+
+        for row in 0..self.depth {
+            let index = self.index(item, row);
+            self.counters[index] = self.counters[index].max(estimate);
+        }
+
+        self.total = self.total.saturating_add(count);
+
+        estimate
+    }
+
+    /// Returns the estimated count of the item.
+    pub fn estimate(&self, item: &[u8]) -> u64 {
+        (0..self.depth)
+            .map(|row| self.counters[self.index(item, row)])
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Removes all the counts.
+    pub fn clear(&mut self) {
+        self.counters.fill(0);
+        self.total = 0;
+    }
+
+    /// Adds the counts of the other sketch to this sketch.
+    ///
+    /// Estimates of the merged sketch are still never less than the true
+    /// counts.
+    ///
+    /// Returns an error if the sketches have different parameters.
+    pub fn merge(&mut self, other: &Self) -> Result<(), Error> {
+        if self.width != other.width || self.depth != other.depth || self.seed != other.seed {
+            return Err(Error::Incompatible);
+        }
+
+        for (counter, &other_counter) in self.counters.iter_mut().zip(&other.counters) {
+            *counter = counter.saturating_add(other_counter);
+        }
+
+        self.total = self.total.saturating_add(other.total);
+
+        Ok(())
+    }
+
+    /// Returns the serialized form of the sketch.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(SKETCH_MAGIC);
+        self.encode(&mut encoder);
+
+        encoder.finish()
+    }
+
+    /// Creates the sketch from the serialized form returned by
+    /// [`Self::to_bytes()`].
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, Error> {
+        let mut decoder = Decoder::new(buf, SKETCH_MAGIC)?;
+        let sketch = Self::decode(&mut decoder)?;
+        decoder.finish()?;
+
+        Ok(sketch)
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write_u8(ENCODING_VERSION);
+        encoder.write_u64(self.width as u64);
+        encoder.write_u64(self.depth as u64);
+        encoder.write_u64(self.seed);
+        encoder.write_u64(self.total);
+
+        for &counter in &self.counters {
+            encoder.write_u64(counter);
+        }
+    }
+
+    fn decode(decoder: &mut Decoder) -> Result<Self, Error> {
+        if decoder.read_u8()? != ENCODING_VERSION {
+            return Err(Error::InvalidEncoding);
+        }
+
+        let width = usize::try_from(decoder.read_u64()?).map_err(|_| Error::InvalidEncoding)?;
+        let depth = usize::try_from(decoder.read_u64()?).map_err(|_| Error::InvalidEncoding)?;
+        let seed = decoder.read_u64()?;
+        let total = decoder.read_u64()?;
+        let len = width.checked_mul(depth).ok_or(Error::InvalidEncoding)?;
+
+        if len == 0 {
+            return Err(Error::InvalidEncoding);
+        }
+
+        let mut counters = Vec::new();
+
+        for _ in 0..len {
+            counters.push(decoder.read_u64()?);
+        }
+
+        Ok(Self {
+            counters,
+            width,
+            depth,
+            seed,
+            total,
+        })
+    }
+
+    fn index(&self, item: &[u8], row: usize) -> usize {
+        let hash = hash(item, self.seed.wrapping_add(row as u64));
+        let column = ((hash as u128 * self.width as u128) >> 64) as usize;
+
+        row * self.width + column
+    }
+}
+
+/// Heavy hitters tracker for finding the most frequent items.
+///
+/// Counts are kept by a [`CountMinSketch`] and the `k` items with the
+/// highest estimates seen so far are kept as candidates. An item that
+/// becomes frequent after the candidates are full replaces the candidate
+/// with the lowest estimate.
+///
+/// Inserting is O(`k`) in addition to the cost of the sketch, so `k`
+/// is intended to be small.
+///
+/// ```rust
+/// use mx3::sketch::{CountMinSketch, TopK};
+///
+/// let mut top = TopK::new(2, CountMinSketch::new(1024, 4));
+///
+/// for path in ["/a", "/b", "/a", "/c", "/a", "/b"] {
+///     top.insert(path.as_bytes());
+/// }
+///
+/// let items = top.top();
+/// assert_eq!(items[0], (&b"/a"[..], 3));
+/// assert_eq!(items[1], (&b"/b"[..], 2));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TopK {
+    k: usize,
+    sketch: CountMinSketch,
+    candidates: Vec<(Vec<u8>, u64)>,
+}
+
+impl TopK {
+    /// Creates an empty tracker of the `k` most frequent items using the
+    /// given empty sketch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is 0.
+    pub fn new(k: usize, sketch: CountMinSketch) -> Self {
+        assert!(k > 0, "k must not be 0");
+
+        Self {
+            k,
+            sketch,
+            candidates: Vec::new(),
+        }
+    }
+
+    /// Returns the maximum number of items tracked.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Returns the sketch holding the counts.
+    pub fn sketch(&self) -> &CountMinSketch {
+        &self.sketch
+    }
+
+    /// Adds 1 to the count of the item.
+    pub fn insert(&mut self, item: &[u8]) {
+        self.add(item, 1);
+    }
+
+    /// Adds to the count of the item.
+    pub fn add(&mut self, item: &[u8], count: u64) {
+        let estimate = self.sketch.add(item, count);
+        self.offer(item, estimate);
+    }
+
+    /// Returns the tracked items and their estimated counts in order of
+    /// decreasing count.
+    pub fn top(&self) -> Vec<(&[u8], u64)> {
+        let mut items: Vec<(&[u8], u64)> = self
+            .candidates
+            .iter()
+            .map(|(item, count)| (item.as_slice(), *count))
+            .collect();
+
+        items.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        items
+    }
+
+    /// Removes all the items and counts.
+    pub fn clear(&mut self) {
+        self.sketch.clear();
+        self.candidates.clear();
+    }
+
+    /// Adds the counts and items of the other tracker to this tracker.
+    ///
+    /// The candidates of both trackers are estimated again using the merged
+    /// sketch. Items that were not a candidate in either tracker are not
+    /// considered.
+    ///
+    /// Returns an error if the sketches have different parameters.
+    pub fn merge(&mut self, other: &Self) -> Result<(), Error> {
+        self.sketch.merge(&other.sketch)?;
+
+        let mut items: Vec<Vec<u8>> = self.candidates.drain(..).map(|(item, _)| item).collect();
+
+        for (item, _) in &other.candidates {
+            if !items.contains(item) {
+                items.push(item.clone());
+            }
+        }
+
+        for item in items {
+            let estimate = self.sketch.estimate(&item);
+            self.offer(&item, estimate);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the serialized form of the tracker.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(TOP_K_MAGIC);
+        encoder.write_u8(ENCODING_VERSION);
+        encoder.write_u64(self.k as u64);
+        encoder.write_u64(self.candidates.len() as u64);
+
+        for (item, count) in &self.candidates {
+            encoder.write_u64(item.len() as u64);
+            encoder.write_bytes(item);
+            encoder.write_u64(*count);
+        }
+
+        self.sketch.encode(&mut encoder);
+
+        encoder.finish()
+    }
+
+    /// Creates the tracker from the serialized form returned by
+    /// [`Self::to_bytes()`].
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, Error> {
+        let mut decoder = Decoder::new(buf, TOP_K_MAGIC)?;
+
+        if decoder.read_u8()? != ENCODING_VERSION {
+            return Err(Error::InvalidEncoding);
+        }
+
+        let k = usize::try_from(decoder.read_u64()?).map_err(|_| Error::InvalidEncoding)?;
+        let count = decoder.read_u64()?;
+
+        if k == 0 || count > k as u64 {
+            return Err(Error::InvalidEncoding);
+        }
+
+        let mut candidates = Vec::new();
+
+        for _ in 0..count {
+            let len = usize::try_from(decoder.read_u64()?).map_err(|_| Error::InvalidEncoding)?;
+            let item = decoder.read(len)?.to_vec();
+            let count = decoder.read_u64()?;
+
+            candidates.push((item, count));
+        }
+
+        let sketch = CountMinSketch::decode(&mut decoder)?;
+        decoder.finish()?;
+
+        Ok(Self {
+            k,
+            sketch,
+            candidates,
+        })
+    }
+
+    fn offer(&mut self, item: &[u8], estimate: u64) {
+        if let Some(candidate) = self
+            .candidates
+            .iter_mut()
+            .find(|(candidate, _)| candidate == item)
+        {
+            candidate.1 = estimate;
+        } else if self.candidates.len() < self.k {
+            self.candidates.push((item.to_vec(), estimate));
+        } else {
+            let (index, &(_, lowest)) = self
+                .candidates
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, (_, count))| *count)
+                .unwrap();
+
+            if estimate > lowest {
+                self.candidates[index] = (item.to_vec(), estimate);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sketch::item;
+
+    #[test]
+    fn test_estimate() {
+        let mut sketch = CountMinSketch::new(2000, 5);
+        assert!(sketch.is_empty());
+
+        for index in 0..1000 {
+            sketch.add(&item(index), index as u64 % 10 + 1);
+        }
+
+        for index in 0..1000 {
+            let estimate = sketch.estimate(&item(index));
+            let count = index as u64 % 10 + 1;
+
+            assert!(estimate >= count);
+            assert!(estimate <= count + 10, "{} {}", estimate, count);
+        }
+
+        assert_eq!(sketch.total(), 5500);
+        assert_eq!(sketch.estimate(b"missing"), 0);
+
+        sketch.clear();
+        assert_eq!(sketch.estimate(&item(1)), 0);
+    }
+
+    #[test]
+    fn test_conservative_update() {
+        let mut sketch = CountMinSketch::new(4, 2);
+
+        // Find an item that shares the column of "a" in the first row only.
+        let other = (0..)
+            .map(item)
+            .find(|other| {
+                sketch.index(other, 0) == sketch.index(b"a", 0)
+                    && sketch.index(other, 1) != sketch.index(b"a", 1)
+            })
+            .unwrap();
+
+        sketch.add(b"a", 5);
+        assert_eq!(sketch.add(&other, 2), 2);
+
+        // A plain update would increment the shared counter to 7.
+        assert_eq!(sketch.counters[sketch.index(b"a", 0)], 5);
+        assert_eq!(sketch.counters[sketch.index(&other, 1)], 2);
+        assert_eq!(sketch.estimate(b"a"), 5);
+
+        let mut sketch = CountMinSketch::new(64, 3);
+        sketch.add(b"a", 5);
+        assert_eq!(sketch.add(b"a", 2), 7);
+        assert_eq!(sketch.counters.iter().sum::<u64>(), 21);
+    }
+
+    #[test]
+    fn test_with_error() {
+        let sketch = CountMinSketch::with_error(0.001, 0.01);
+
+        assert_eq!(sketch.width(), 2719);
+        assert_eq!(sketch.depth(), 5);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut sketch1 = CountMinSketch::new(100, 4);
+        let mut sketch2 = CountMinSketch::new(100, 4);
+
+        sketch1.add(b"a", 3);
+        sketch2.add(b"a", 4);
+        sketch2.add(b"b", 1);
+
+        sketch1.merge(&sketch2).unwrap();
+        assert!(sketch1.estimate(b"a") >= 7);
+        assert!(sketch1.estimate(b"b") >= 1);
+        assert_eq!(sketch1.total(), 8);
+
+        assert_eq!(
+            sketch1.merge(&CountMinSketch::with_seed(100, 4, 1)),
+            Err(Error::Incompatible)
+        );
+        assert_eq!(
+            sketch1.merge(&CountMinSketch::new(100, 3)),
+            Err(Error::Incompatible)
+        );
+    }
+
+    #[test]
+    fn test_sketch_serialization() {
+        let mut sketch = CountMinSketch::with_seed(10, 2, 5);
+        sketch.add(b"a", 3);
+
+        let bytes = sketch.to_bytes();
+        assert_eq!(bytes.len(), 4 + 1 + 4 * 8 + 20 * 8);
+        assert_eq!(CountMinSketch::from_bytes(&bytes), Ok(sketch));
+        assert_eq!(
+            CountMinSketch::from_bytes(&bytes[0..bytes.len() - 1]),
+            Err(Error::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn test_top_k() {
+        let mut top = TopK::new(3, CountMinSketch::new(256, 4));
+
+        for index in 0..1000u32 {
+            // Items 0, 1, 2 are frequent among many infrequent items.
+            top.insert(&item(index % 3));
+            top.insert(&item(index + 100));
+        }
+
+        let items = top.top();
+        assert_eq!(items.len(), 3);
+
+        for (index, (item_bytes, count)) in items.iter().enumerate() {
+            assert!((0..3).contains(&u32::from_le_bytes((*item_bytes).try_into().unwrap())));
+            assert!(*count >= 333, "{} {}", index, count);
+        }
+    }
+
+    #[test]
+    fn test_top_k_merge() {
+        let mut top1 = TopK::new(2, CountMinSketch::new(256, 4));
+        let mut top2 = TopK::new(2, CountMinSketch::new(256, 4));
+
+        top1.add(b"a", 10);
+        top1.add(b"b", 5);
+        top2.add(b"c", 8);
+        top2.add(b"b", 6);
+
+        top1.merge(&top2).unwrap();
+
+        assert_eq!(top1.top(), [(&b"b"[..], 11), (&b"a"[..], 10)]);
+
+        let top3 = TopK::new(2, CountMinSketch::new(128, 4));
+        assert_eq!(top1.merge(&top3), Err(Error::Incompatible));
+    }
+
+    #[test]
+    fn test_top_k_serialization() {
+        let mut top = TopK::new(2, CountMinSketch::new(16, 2));
+        top.add(b"apple", 3);
+        top.add(b"banana", 1);
+
+        let bytes = top.to_bytes();
+        let decoded = TopK::from_bytes(&bytes).unwrap();
+
+        assert_eq!(decoded, top);
+        assert_eq!(
+            TopK::from_bytes(&bytes[0..bytes.len() - 1]),
+            Err(Error::InvalidEncoding)
+        );
+        assert_eq!(
+            TopK::from_bytes(&top.sketch().to_bytes()),
+            Err(Error::InvalidEncoding)
+        );
+    }
+}
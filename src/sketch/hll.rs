@@ -0,0 +1,575 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::LN_2;
+
+use super::{Decoder, Encoder, Error};
+use crate::v3::{hash, mix};
+
+const MAGIC: &[u8; 4] = b"MX3H";
+const ENCODING_VERSION: u8 = 1;
+const SPARSE_TAG: u8 = 0;
+const DENSE_TAG: u8 = 1;
+
+/// HyperLogLog sketch for estimating the number of distinct items.
+///
+/// The precision `P` sets the number of registers to 2<sup>`P`</sup>.
+/// The relative standard error of the estimate is about
+/// 1.04 / sqrt(2<sup>`P`</sup>), such as 1.6% for a precision of 12.
+/// The precision must be between 4 and 18 inclusive.
+///
+/// Integers are ingested using [`crate::v3::mix()`] and bytes are ingested
+/// using [`crate::v3::hash()`]. The estimate uses the improved estimator
+/// described in "New cardinality estimation algorithms for HyperLogLog
+/// sketches" by Ertl which corrects the bias for small and large
+/// cardinalities without empirical tables.
+///
+/// Until enough registers are set, the sketch uses a sparse representation
+/// that stores only the set registers.
+///
+/// ```rust
+/// use mx3::sketch::Hll;
+///
+/// let mut hll = Hll::<12>::new();
+///
+/// for visitor in 0..10000u64 {
+///     hll.insert_u64(visitor % 5000);
+/// }
+///
+/// let estimate = hll.estimate();
+/// assert!((4800.0..5200.0).contains(&estimate));
+/// ```
+///
+/// An invalid precision fails to compile:
+///
+/// ```compile_fail
+/// let hll = mx3::sketch::Hll::<3>::new();
+/// ```
+///
+/// ```compile_fail
+/// let hll = mx3::sketch::Hll::<19>::from_bytes(&[]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Hll<const P: u8> {
+    repr: Repr,
+}
+
+#[derive(Clone, Debug)]
+enum Repr {
+    /// Sorted by register index, each entry being `index << 8 | rank`.
+    Sparse(Vec<u32>),
+    Dense(Vec<u8>),
+}
+
+impl<const P: u8> Hll<P> {
+    const VALID_PRECISION: () = assert!(P >= 4 && P <= 18, "precision must be between 4 and 18");
+
+    const NUM_REGISTERS: usize = 1 << P;
+
+    /// Maximum rank, which is one more than the number of hash bits
+    /// remaining after the register index.
+    const MAX_RANK: u8 = 65 - P;
+
+    /// Creates an empty sketch.
+    pub fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_PRECISION;
+
+        Self {
+            repr: Repr::Sparse(Vec::new()),
+        }
+    }
+
+    /// Returns the number of registers.
+    pub fn num_registers(&self) -> usize {
+        Self::NUM_REGISTERS
+    }
+
+    /// Returns whether no items were inserted.
+    pub fn is_empty(&self) -> bool {
+        match &self.repr {
+            Repr::Sparse(entries) => entries.is_empty(),
+            Repr::Dense(registers) => registers.iter().all(|&rank| rank == 0),
+        }
+    }
+
+    /// Returns whether the sketch uses the sparse representation.
+    pub fn is_sparse(&self) -> bool {
+        matches!(self.repr, Repr::Sparse(_))
+    }
+
+    /// Inserts the integer.
+    pub fn insert_u64(&mut self, item: u64) {
+        self.insert_hash(mix(item));
+    }
+
+    /// Inserts the bytes.
+    pub fn insert(&mut self, item: &[u8]) {
+        self.insert_hash(hash(item, 0));
+    }
+
+    /// Inserts an item that was already hashed to a uniformly distributed
+    /// integer.
+    pub fn insert_hash(&mut self, hash: u64) {
+        let index = (hash >> (64 - P)) as usize;
+        let rank = ((hash << P).leading_zeros() as u8 + 1).min(Self::MAX_RANK);
+
+        self.update(index, rank);
+    }
+
+    /// Returns the estimated number of distinct items inserted.
+    pub fn estimate(&self) -> f64 {
+        let mut histogram = [0u32; 65];
+
+        match &self.repr {
+            Repr::Sparse(entries) => {
+                histogram[0] = (Self::NUM_REGISTERS - entries.len()) as u32;
+
+                for &entry in entries {
+                    histogram[(entry & 0xff) as usize] += 1;
+                }
+            }
+            Repr::Dense(registers) => {
+                for &rank in registers {
+                    histogram[rank as usize] += 1;
+                }
+            }
+        }
+
+        let m = Self::NUM_REGISTERS as f64;
+        let q = Self::MAX_RANK as usize - 1;
+        let mut z = m * tau(1.0 - histogram[q + 1] as f64 / m);
+
+        for k in (1..=q).rev() {
+            z = 0.5 * (z + histogram[k] as f64);
+        }
+
+        z += m * sigma(histogram[0] as f64 / m);
+
+        m * m / (2.0 * LN_2 * z)
+    }
+
+    /// Adds the items of the other sketch to this sketch.
+    ///
+    /// The result is the same as if the items were inserted into this sketch.
+    pub fn merge(&mut self, other: &Self) {
+        match (&mut self.repr, &other.repr) {
+            (Repr::Sparse(entries), Repr::Sparse(other_entries)) => {
+                *entries = merge_sparse(entries, other_entries);
+                self.densify_if_large();
+            }
+            (_, Repr::Sparse(other_entries)) => {
+                for &entry in other_entries {
+                    self.update((entry >> 8) as usize, entry as u8);
+                }
+            }
+            (_, Repr::Dense(other_registers)) => {
+                let registers = self.densify();
+
+                for (rank, &other_rank) in registers.iter_mut().zip(other_registers) {
+                    *rank = (*rank).max(other_rank);
+                }
+            }
+        }
+    }
+
+    /// Removes all the items.
+    pub fn clear(&mut self) {
+        self.repr = Repr::Sparse(Vec::new());
+    }
+
+    /// Returns the serialized form of the sketch.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(MAGIC);
+        encoder.write_u8(ENCODING_VERSION);
+        encoder.write_u8(P);
+
+        match &self.repr {
+            Repr::Sparse(entries) => {
+                encoder.write_u8(SPARSE_TAG);
+                encoder.write_u32(entries.len() as u32);
+
+                for &entry in entries {
+                    encoder.write_u32(entry);
+                }
+            }
+            Repr::Dense(registers) => {
+                encoder.write_u8(DENSE_TAG);
+                encoder.write_bytes(registers);
+            }
+        }
+
+        encoder.finish()
+    }
+
+    /// Creates the sketch from the serialized form returned by
+    /// [`Self::to_bytes()`].
+    ///
+    /// Returns [`Error::Incompatible`] if the sketch has a different
+    /// precision, and [`Error::InvalidEncoding`] if the precision is not
+    /// between 4 and 18 or the number of registers does not match it.
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, Error> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_PRECISION;
+
+        let mut decoder = Decoder::new(buf, MAGIC)?;
+
+        if decoder.read_u8()? != ENCODING_VERSION {
+            return Err(Error::InvalidEncoding);
+        }
+
+        let precision = decoder.read_u8()?;
+
+        if !(4..=18).contains(&precision) {
+            return Err(Error::InvalidEncoding);
+        }
+
+        if precision != P {
+            return Err(Error::Incompatible);
+        }
+
+        let repr = match decoder.read_u8()? {
+            SPARSE_TAG => {
+                let count = decoder.read_u32()? as usize;
+
+                if count > Self::NUM_REGISTERS {
+                    return Err(Error::InvalidEncoding);
+                }
+
+                let mut entries = Vec::with_capacity(count);
+
+                for _ in 0..count {
+                    let entry = decoder.read_u32()?;
+                    let rank = entry as u8;
+                    let index = entry >> 8;
+
+                    if rank == 0
+                        || rank > Self::MAX_RANK
+                        || index as usize >= Self::NUM_REGISTERS
+                        || entries.last().is_some_and(|&last: &u32| last >> 8 >= index)
+                    {
+                        return Err(Error::InvalidEncoding);
+                    }
+
+                    entries.push(entry);
+                }
+
+                Repr::Sparse(entries)
+            }
+            DENSE_TAG => {
+                let registers = decoder.read(Self::NUM_REGISTERS)?;
+
+                if registers.iter().any(|&rank| rank > Self::MAX_RANK) {
+                    return Err(Error::InvalidEncoding);
+                }
+
+                Repr::Dense(registers.to_vec())
+            }
+            _ => return Err(Error::InvalidEncoding),
+        };
+
+        decoder.finish()?;
+
+        Ok(Self { repr })
+    }
+
+    fn update(&mut self, index: usize, rank: u8) {
+        match &mut self.repr {
+            Repr::Sparse(entries) => {
+                let entry = (index as u32) << 8 | rank as u32;
+
+                match entries.binary_search_by_key(&index, |&entry| (entry >> 8) as usize) {
+                    Ok(position) => {
+                        if entries[position] & 0xff < rank as u32 {
+                            entries[position] = entry;
+                        }
+                    }
+                    Err(position) => {
+                        entries.insert(position, entry);
+                        self.densify_if_large();
+                    }
+                }
+            }
+            Repr::Dense(registers) => {
+                registers[index] = registers[index].max(rank);
+            }
+        }
+    }
+
+    /// Switches to the dense representation once the sparse representation
+    /// uses more memory.
+    fn densify_if_large(&mut self) {
+        if let Repr::Sparse(entries) = &self.repr {
+            if entries.len() * 4 > Self::NUM_REGISTERS {
+                self.densify();
+            }
+        }
+    }
+
+    fn densify(&mut self) -> &mut Vec<u8> {
+        if let Repr::Sparse(entries) = &self.repr {
+            let mut registers = vec![0u8; Self::NUM_REGISTERS];
+
+            for &entry in entries {
+                registers[(entry >> 8) as usize] = entry as u8;
+            }
+
+            self.repr = Repr::Dense(registers);
+        }
+
+        match &mut self.repr {
+            Repr::Dense(registers) => registers,
+            Repr::Sparse(_) => unreachable!(),
+        }
+    }
+}
+
+impl<const P: u8> Default for Hll<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn merge_sparse(entries: &[u32], other_entries: &[u32]) -> Vec<u32> {
+    let mut output = Vec::with_capacity(entries.len() + other_entries.len());
+    let mut left = entries.iter().peekable();
+    let mut right = other_entries.iter().peekable();
+
+    loop {
+        let entry = match (left.peek(), right.peek()) {
+            (Some(&&a), Some(&&b)) if a >> 8 == b >> 8 => {
+                left.next();
+                right.next();
+                a.max(b)
+            }
+            (Some(&&a), Some(&&b)) if a < b => {
+                left.next();
+                a
+            }
+            (_, Some(&&b)) => {
+                right.next();
+                b
+            }
+            (Some(&&a), None) => {
+                left.next();
+                a
+            }
+            (None, None) => break,
+        };
+
+        output.push(entry);
+    }
+
+    output
+}
+
+/// The sigma function of Ertl's improved estimator.
+fn sigma(mut x: f64) -> f64 {
+    if x == 1.0 {
+        return f64::INFINITY;
+    }
+
+    let mut y = 1.0;
+    let mut z = x;
+
+    loop {
+        x *= x;
+        let previous = z;
+        z += x * y;
+        y += y;
+
+        if z == previous {
+            return z;
+        }
+    }
+}
+
+/// The tau function of Ertl's improved estimator.
+fn tau(mut x: f64) -> f64 {
+    if x == 0.0 || x == 1.0 {
+        return 0.0;
+    }
+
+    let mut y = 1.0;
+    let mut z = 1.0 - x;
+
+    loop {
+        x = libm::sqrt(x);
+        let previous = z;
+        y *= 0.5;
+        z -= (1.0 - x) * (1.0 - x) * y;
+
+        if z == previous {
+            return z / 3.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_estimate<const P: u8>(hll: &Hll<P>, expected: f64, tolerance: f64) {
+        let estimate = hll.estimate();
+        let error = (estimate - expected).abs() / expected.max(1.0);
+
+        assert!(error <= tolerance, "{} {}", estimate, expected);
+    }
+
+    #[test]
+    fn test_empty() {
+        let hll = Hll::<12>::new();
+
+        assert!(hll.is_empty());
+        assert!(hll.is_sparse());
+        assert_eq!(hll.num_registers(), 4096);
+        assert_eq!(hll.estimate(), 0.0);
+    }
+
+    #[test]
+    fn test_estimate() {
+        let mut hll = Hll::<12>::new();
+        let mut count = 0u64;
+
+        for checkpoint in [1, 10, 100, 1000, 10000, 100000, 1000000] {
+            while count < checkpoint {
+                hll.insert_u64(count);
+                count += 1;
+            }
+
+            assert_estimate(&hll, checkpoint as f64, 0.05);
+        }
+
+        assert!(!hll.is_sparse());
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let mut hll = Hll::<10>::new();
+
+        for index in 0..100000u32 {
+            hll.insert(&(index % 500).to_le_bytes());
+        }
+
+        assert_estimate(&hll, 500.0, 0.1);
+    }
+
+    #[test]
+    fn test_sparse_dense_same_estimate() {
+        let mut sparse = Hll::<12>::new();
+
+        for index in 0..500 {
+            sparse.insert_u64(index);
+        }
+
+        assert!(sparse.is_sparse());
+
+        let mut dense = sparse.clone();
+        dense.densify();
+
+        assert_eq!(sparse.estimate(), dense.estimate());
+
+        sparse.insert_u64(500);
+        dense.insert_u64(500);
+        assert_eq!(sparse.estimate(), dense.estimate());
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut expected = Hll::<8>::new();
+
+        for (len1, len2) in [(10, 20), (10, 5000), (5000, 10), (5000, 5000)] {
+            let mut hll1 = Hll::<8>::new();
+            let mut hll2 = Hll::<8>::new();
+            expected.clear();
+
+            for index in 0..len1 {
+                hll1.insert_u64(index);
+                expected.insert_u64(index);
+            }
+
+            for index in 0..len2 {
+                hll2.insert_u64(index + 1_000_000);
+                expected.insert_u64(index + 1_000_000);
+            }
+
+            hll1.merge(&hll2);
+
+            assert_eq!(hll1.estimate(), expected.estimate());
+        }
+    }
+
+    #[test]
+    fn test_serialization() {
+        let mut hll = Hll::<6>::new();
+        hll.insert_u64(1);
+        hll.insert_u64(2);
+
+        let bytes = hll.to_bytes();
+        assert!(hll.is_sparse());
+        assert_eq!(bytes.len(), 4 + 3 + 4 + 2 * 4);
+        assert_eq!(Hll::<6>::from_bytes(&bytes).unwrap().to_bytes(), bytes);
+
+        for index in 0..1000 {
+            hll.insert_u64(index);
+        }
+
+        let bytes = hll.to_bytes();
+        assert!(!hll.is_sparse());
+        assert_eq!(bytes.len(), 4 + 3 + 64);
+
+        let decoded = Hll::<6>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert_eq!(decoded.estimate(), hll.estimate());
+
+        assert!(matches!(
+            Hll::<7>::from_bytes(&bytes),
+            Err(Error::Incompatible)
+        ));
+        assert!(matches!(
+            Hll::<6>::from_bytes(&bytes[0..bytes.len() - 1]),
+            Err(Error::InvalidEncoding)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_dense_encoding() {
+        let encode = |precision: u8, num_registers: usize| {
+            let mut encoder = Encoder::new(MAGIC);
+            encoder.write_u8(ENCODING_VERSION);
+            encoder.write_u8(precision);
+            encoder.write_u8(DENSE_TAG);
+            encoder.write_bytes(&std::vec![0; num_registers]);
+            encoder.finish()
+        };
+
+        assert!(Hll::<6>::from_bytes(&encode(6, 64)).is_ok());
+
+        for (precision, num_registers) in [(6, 63), (6, 65), (3, 8), (19, 64), (255, 64)] {
+            assert!(
+                matches!(
+                    Hll::<6>::from_bytes(&encode(precision, num_registers)),
+                    Err(Error::InvalidEncoding)
+                ),
+                "{} {}",
+                precision,
+                num_registers
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_sparse_encoding() {
+        let mut encoder = Encoder::new(MAGIC);
+        encoder.write_u8(ENCODING_VERSION);
+        encoder.write_u8(6);
+        encoder.write_u8(SPARSE_TAG);
+        encoder.write_u32(2);
+        encoder.write_u32(2 << 8 | 1);
+        encoder.write_u32(1 << 8 | 1);
+
+        assert!(matches!(
+            Hll::<6>::from_bytes(&encoder.finish()),
+            Err(Error::InvalidEncoding)
+        ));
+    }
+}
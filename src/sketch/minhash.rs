@@ -0,0 +1,236 @@
+use crate::v3::{hash, mix};
+
+/// MinHash signature for estimating the Jaccard similarity of sets.
+///
+/// The signature holds the minimum value of each of `K` permutations over
+/// the hashes of the items. Each permutation is [`crate::v3::mix()`] of the
+/// item hash XORed with a distinct seed. The standard error of the
+/// similarity estimate is about 1 / sqrt(`K`).
+///
+/// Documents are typically inserted as shingles, the overlapping sequences
+/// of words or bytes, such as by [`Self::insert_shingles()`].
+///
+/// ```rust
+/// use mx3::sketch::MinHash;
+///
+/// let a = MinHash::<128>::from_shingles(b"the quick brown fox jumps over the lazy dog", 4);
+/// let b = MinHash::<128>::from_shingles(b"the quick brown fox jumped over the lazy dog", 4);
+/// let c = MinHash::<128>::from_shingles(b"lorem ipsum dolor sit amet", 4);
+///
+/// assert!(a.jaccard(&b) > 0.5);
+/// assert!(a.jaccard(&c) < 0.1);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MinHash<const K: usize> {
+    signature: [u64; K],
+}
+
+impl<const K: usize> MinHash<K> {
+    /// Creates a signature of the empty set.
+    pub fn new() -> Self {
+        Self {
+            signature: [u64::MAX; K],
+        }
+    }
+
+    /// Creates a signature from the values returned by [`Self::signature()`].
+    pub fn from_signature(signature: [u64; K]) -> Self {
+        Self { signature }
+    }
+
+    /// Creates a signature of the overlapping windows of `width` bytes.
+    ///
+    /// See [`Self::insert_shingles()`].
+    pub fn from_shingles(data: &[u8], width: usize) -> Self {
+        let mut minhash = Self::new();
+        minhash.insert_shingles(data, width);
+
+        minhash
+    }
+
+    /// Returns the minimum value of each permutation.
+    pub fn signature(&self) -> &[u64; K] {
+        &self.signature
+    }
+
+    /// Returns whether no items were inserted.
+    pub fn is_empty(&self) -> bool {
+        self.signature.iter().all(|&value| value == u64::MAX)
+    }
+
+    /// Inserts the item.
+    pub fn insert(&mut self, item: &[u8]) {
+        self.insert_hash(hash(item, 0));
+    }
+
+    /// Inserts the overlapping windows of `width` bytes as items.
+    ///
+    /// If the data is shorter than the width, the whole data is inserted
+    /// as a single item.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is 0.
+    pub fn insert_shingles(&mut self, data: &[u8], width: usize) {
+        assert!(width > 0, "width must not be 0");
+
+        if data.len() < width {
+            self.insert(data);
+        } else {
+            for shingle in data.windows(width) {
+                self.insert(shingle);
+            }
+        }
+    }
+
+    /// Inserts an item that was already hashed to an integer.
+    pub fn insert_hash(&mut self, hash: u64) {
+        for (index, value) in self.signature.iter_mut().enumerate() {
+            *value = (*value).min(mix(hash ^ permutation_seed(index)));
+        }
+    }
+
+    /// Adds the items of the other signature to this signature.
+    ///
+    /// The result is the signature of the union of the sets.
+    pub fn merge(&mut self, other: &Self) {
+        for (value, &other_value) in self.signature.iter_mut().zip(&other.signature) {
+            *value = (*value).min(other_value);
+        }
+    }
+
+    /// Returns the estimated Jaccard similarity between the sets.
+    ///
+    /// Two empty sets are equal, so their similarity is defined as 1. The
+    /// similarity of an empty set and a non-empty set is 0.
+    ///
+    /// See [`jaccard()`].
+    pub fn jaccard(&self, other: &Self) -> f64 {
+        match (self.is_empty(), other.is_empty()) {
+            (true, true) => 1.0,
+            (true, false) | (false, true) => 0.0,
+            (false, false) => jaccard(&self.signature, &other.signature),
+        }
+    }
+}
+
+impl<const K: usize> Default for MinHash<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the estimated Jaccard similarity from two MinHash signatures.
+///
+/// The estimate is the fraction of the positions with equal values.
+/// Returns 0 if the signatures have no values.
+///
+/// The signatures of empty sets are equal, so the estimate for two empty
+/// sets is 1. Use [`MinHash::jaccard()`] to also handle one empty set.
+///
+/// # Panics
+///
+/// Panics if the signatures have different lengths.
+pub fn jaccard(signature: &[u64], other_signature: &[u64]) -> f64 {
+    assert_eq!(
+        signature.len(),
+        other_signature.len(),
+        "signatures must have the same length"
+    );
+
+    if signature.is_empty() {
+        return 0.0;
+    }
+
+    let equal = signature
+        .iter()
+        .zip(other_signature)
+        .filter(|(a, b)| a == b)
+        .count();
+
+    equal as f64 / signature.len() as f64
+}
+
+fn permutation_seed(index: usize) -> u64 {
+    mix(index as u64 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minhash_of_range<const K: usize>(range: core::ops::Range<u32>) -> MinHash<K> {
+        let mut minhash = MinHash::new();
+
+        for item in range {
+            minhash.insert(&item.to_le_bytes());
+        }
+
+        minhash
+    }
+
+    #[test]
+    fn test_jaccard_estimate() {
+        let a = minhash_of_range::<512>(0..1000);
+        let b = minhash_of_range::<512>(500..1500);
+        let c = minhash_of_range::<512>(2000..3000);
+
+        let estimate = a.jaccard(&b);
+        assert!((0.28..0.39).contains(&estimate), "{}", estimate);
+
+        assert_eq!(a.jaccard(&a), 1.0);
+        assert!(a.jaccard(&c) < 0.02);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut a = minhash_of_range::<64>(0..100);
+        let b = minhash_of_range::<64>(100..200);
+        a.merge(&b);
+
+        assert_eq!(a, minhash_of_range::<64>(0..200));
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut minhash = MinHash::<16>::new();
+        assert!(minhash.is_empty());
+
+        minhash.insert_shingles(b"ab", 3);
+        assert!(!minhash.is_empty());
+
+        let mut expected = MinHash::<16>::new();
+        expected.insert(b"ab");
+        assert_eq!(minhash, expected);
+    }
+
+    #[test]
+    fn test_jaccard_empty() {
+        let empty = MinHash::<16>::new();
+        let other = MinHash::<16>::from_shingles(b"hello world", 3);
+
+        assert_eq!(empty.jaccard(&empty), 1.0);
+        assert_eq!(empty.jaccard(&other), 0.0);
+        assert_eq!(other.jaccard(&empty), 0.0);
+    }
+
+    #[test]
+    fn test_from_signature() {
+        let minhash = MinHash::<8>::from_shingles(b"hello world", 3);
+        let copy = MinHash::from_signature(*minhash.signature());
+
+        assert_eq!(copy, minhash);
+    }
+
+    #[test]
+    fn test_jaccard_function() {
+        assert_eq!(jaccard(&[], &[]), 0.0);
+        assert_eq!(jaccard(&[1, 2, 3, 4], &[1, 2, 5, 6]), 0.5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_jaccard_different_lengths() {
+        jaccard(&[1, 2], &[1]);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::v3::hash;
+
+/// Accumulator for computing 64-bit SimHash fingerprints.
+///
+/// Each feature is hashed with [`crate::v3::hash()`] and votes for or against
+/// each bit of the fingerprint. Similar documents have fingerprints with a
+/// small [`hamming()`] distance.
+///
+/// Features are typically the shingles of the document, the overlapping
+/// sequences of words or bytes, such as by [`Self::insert_shingles()`].
+///
+/// ```rust
+/// use mx3::sketch::{hamming, SimHash};
+///
+/// let a = SimHash::from_shingles(b"the quick brown fox jumps over the lazy dog", 4).finish();
+/// let b = SimHash::from_shingles(b"the quick brown fox jumped over the lazy dog", 4).finish();
+/// let c = SimHash::from_shingles(b"lorem ipsum dolor sit amet", 4).finish();
+///
+/// assert!(hamming(a, b) < hamming(a, c));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimHash {
+    weights: [i64; 64],
+}
+
+impl SimHash {
+    /// Creates an accumulator without features.
+    pub fn new() -> Self {
+        Self { weights: [0; 64] }
+    }
+
+    /// Creates an accumulator of the overlapping windows of `width` bytes.
+    ///
+    /// See [`Self::insert_shingles()`].
+    pub fn from_shingles(data: &[u8], width: usize) -> Self {
+        let mut simhash = Self::new();
+        simhash.insert_shingles(data, width);
+
+        simhash
+    }
+
+    /// Adds the feature with a weight of 1.
+    pub fn insert(&mut self, feature: &[u8]) {
+        self.insert_weighted(feature, 1);
+    }
+
+    /// Adds the feature with the given weight.
+    pub fn insert_weighted(&mut self, feature: &[u8], weight: i64) {
+        self.insert_hash(hash(feature, 0), weight);
+    }
+
+    /// Adds the overlapping windows of `width` bytes as features.
+    ///
+    /// If the data is shorter than the width, the whole data is added as a
+    /// single feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is 0.
+    pub fn insert_shingles(&mut self, data: &[u8], width: usize) {
+        assert!(width > 0, "width must not be 0");
+
+        if data.len() < width {
+            self.insert(data);
+        } else {
+            for shingle in data.windows(width) {
+                self.insert(shingle);
+            }
+        }
+    }
+
+    /// Adds a feature that was already hashed to an integer.
+    pub fn insert_hash(&mut self, hash: u64, weight: i64) {
+        for (bit, total) in self.weights.iter_mut().enumerate() {
+            if hash & (1 << bit) != 0 {
+                *total = total.saturating_add(weight);
+            } else {
+                *total = total.saturating_sub(weight);
+            }
+        }
+    }
+
+    /// Returns the fingerprint of the features added so far.
+    ///
+    /// A bit is set if its total weight is positive.
+    pub fn finish(&self) -> u64 {
+        self.weights
+            .iter()
+            .enumerate()
+            .filter(|(_, &total)| total > 0)
+            .fold(0, |fingerprint, (bit, _)| fingerprint | (1 << bit))
+    }
+}
+
+impl Default for SimHash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the number of bits that differ between the fingerprints.
+pub fn hamming(fingerprint: u64, other_fingerprint: u64) -> u32 {
+    (fingerprint ^ other_fingerprint).count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_feature() {
+        let mut simhash = SimHash::new();
+        assert_eq!(simhash.finish(), 0);
+
+        simhash.insert(b"apple");
+        assert_eq!(simhash.finish(), hash(b"apple", 0));
+    }
+
+    #[test]
+    fn test_weights() {
+        let mut simhash = SimHash::new();
+        simhash.insert_hash(0xff00, 1);
+        simhash.insert_hash(0x0ff0, 2);
+
+        assert_eq!(simhash.finish(), 0x0ff0);
+
+        simhash.insert_hash(0x0ff0, -3);
+        assert_eq!(simhash.finish(), 0xf000);
+    }
+
+    #[test]
+    fn test_near_duplicates() {
+        let text = b"It was the best of times, it was the worst of times, it was the age of \
+            wisdom, it was the age of foolishness, it was the epoch of belief";
+        let mut edited = *text;
+        edited[20] = b'X';
+
+        let a = SimHash::from_shingles(text, 5).finish();
+        let b = SimHash::from_shingles(&edited, 5).finish();
+        let c = SimHash::from_shingles(b"Call me Ishmael. Some years ago, never mind how long", 5)
+            .finish();
+
+        assert!(hamming(a, b) <= 10, "{}", hamming(a, b));
+        assert!(hamming(a, c) >= 16, "{}", hamming(a, c));
+    }
+
+    #[test]
+    fn test_hamming() {
+        assert_eq!(hamming(0, 0), 0);
+        assert_eq!(hamming(0, u64::MAX), 64);
+        assert_eq!(hamming(0b1010, 0b0110), 2);
+    }
+}
@@ -0,0 +1,587 @@
+//! Statistical quality tests for the mixers and generators.
+//!
+//! These tests are for checking the quality of the algorithms on your own
+//! machine. They are small versions of the tests found in test batteries
+//! such as TestU01 and are not a replacement for them.
+//!
+//! Each test returns a [`TestResult`] with a p-value. The p-value is the
+//! probability of an ideal mixer or generator producing a statistic at
+//! least as extreme. Values very close to 0 indicate a failure.
+//! When running many tests, some p-values below 0.01 are expected by chance
+//! alone.
+//!
+//! ```rust
+//! use mx3::stats;
+//!
+//! for result in stats::run_all(mx3::Version::V3, 1000) {
+//!     println!("{}", result);
+//! }
+//! ```
+use core::fmt::{Display, Formatter};
+use std::vec;
+use std::vec::Vec;
+
+use rand_core::RngCore;
+
+use crate::Version;
+
+/// Result of a statistical test.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestResult {
+    /// Name of the test.
+    pub name: &'static str,
+    /// Value of the test statistic.
+    pub statistic: f64,
+    /// Probability of an ideal source producing a statistic at least as
+    /// extreme as [`Self::statistic`].
+    pub p_value: f64,
+}
+
+impl Display for TestResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{:<20} statistic={:<14.4} p={:.6}",
+            self.name, self.statistic, self.p_value
+        )
+    }
+}
+
+/// Runs all the tests for the given version.
+///
+/// The amount of work is proportional to `samples`.
+/// The mixer inputs are generated by the version's own generator with a
+/// fixed seed so the results are deterministic.
+///
+/// # Panics
+///
+/// Panics if `samples` is less than 100.
+pub fn run_all(version: Version, samples: u64) -> Vec<TestResult> {
+    assert!(samples >= 100, "samples must be at least 100");
+
+    let mut inputs = version.rng(0x6d78_3373_7461_7473);
+
+    vec![
+        strict_avalanche(|x| version.mix(x), &mut inputs, samples * 10),
+        bit_independence(|x| version.mix(x), &mut inputs, samples / 10 + 1),
+        bucket_chi_square(&mut version.rng(1), 8, samples * 100),
+        birthday_spacings(&mut version.rng(2), samples / 10 + 1),
+        gap(&mut version.rng(3), samples * 10),
+    ]
+}
+
+/// Strict avalanche criterion test for a mixer.
+///
+/// Flipping any input bit should flip each output bit with probability 1/2.
+/// For each of the `samples` inputs, every input bit is flipped and the
+/// flipped output bits are counted. The counts of the 64×64 pairs of
+/// input and output bits are checked with a chi-square test.
+///
+/// # Panics
+///
+/// Panics if `samples` is less than 10.
+pub fn strict_avalanche<M, R>(mix: M, inputs: &mut R, samples: u64) -> TestResult
+where
+    M: Fn(u64) -> u64,
+    R: RngCore,
+{
+    assert!(samples >= 10, "samples must be at least 10");
+
+    let mut counts = vec![0u64; 64 * 64];
+
+    for _ in 0..samples {
+        let x = inputs.next_u64();
+        let output = mix(x);
+
+        for input_bit in 0..64 {
+            let flipped = output ^ mix(x ^ (1 << input_bit));
+
+            for (output_bit, count) in counts[input_bit * 64..(input_bit + 1) * 64]
+                .iter_mut()
+                .enumerate()
+            {
+                *count += (flipped >> output_bit) & 1;
+            }
+        }
+    }
+
+    let statistic = binomial_chi_square(&counts, samples, 0.5);
+
+    TestResult {
+        name: "strict avalanche",
+        statistic,
+        p_value: chi_square_upper_tail(statistic, counts.len() as f64),
+    }
+}
+
+/// Bit independence criterion test for a mixer.
+///
+/// When any input bit is flipped, any two output bits should flip
+/// independently of each other. For each input bit and each pair of output
+/// bits, the number of times both output bits flipped is checked against
+/// the expected probability of 1/4 with a chi-square test.
+///
+/// # Panics
+///
+/// Panics if `samples` is less than 10.
+pub fn bit_independence<M, R>(mix: M, inputs: &mut R, samples: u64) -> TestResult
+where
+    M: Fn(u64) -> u64,
+    R: RngCore,
+{
+    assert!(samples >= 10, "samples must be at least 10");
+
+    const PAIRS: usize = 64 * 63 / 2;
+    let mut counts = vec![0u64; 64 * PAIRS];
+
+    for _ in 0..samples {
+        let x = inputs.next_u64();
+        let output = mix(x);
+
+        for input_bit in 0..64 {
+            let flipped = output ^ mix(x ^ (1 << input_bit));
+            let pair_counts = &mut counts[input_bit * PAIRS..(input_bit + 1) * PAIRS];
+            let mut remain = flipped;
+
+            while remain != 0 {
+                let first = remain.trailing_zeros() as usize;
+                remain &= remain - 1;
+
+                let mut others = remain;
+
+                while others != 0 {
+                    let second = others.trailing_zeros() as usize;
+                    others &= others - 1;
+
+                    pair_counts[pair_index(first, second)] += 1;
+                }
+            }
+        }
+    }
+
+    let statistic = binomial_chi_square(&counts, samples, 0.25);
+
+    TestResult {
+        name: "bit independence",
+        statistic,
+        p_value: chi_square_upper_tail(statistic, counts.len() as f64),
+    }
+}
+
+fn pair_index(first: usize, second: usize) -> usize {
+    debug_assert!(first < second);
+    first * (127 - first) / 2 + second - first - 1
+}
+
+/// Chi-square test of uniformity for a generator.
+///
+/// The top `bucket_bits` bits of `samples` outputs are counted in
+/// 2<sup>`bucket_bits`</sup> buckets.
+///
+/// # Panics
+///
+/// Panics if `bucket_bits` is not between 1 and 24, or `samples` is less
+/// than 5 per bucket.
+pub fn bucket_chi_square<R: RngCore>(rng: &mut R, bucket_bits: u32, samples: u64) -> TestResult {
+    assert!(
+        (1..=24).contains(&bucket_bits),
+        "bucket_bits must be between 1 and 24"
+    );
+    assert!(
+        samples >= 5 << bucket_bits,
+        "samples must be at least 5 per bucket"
+    );
+
+    let mut counts = vec![0u64; 1 << bucket_bits];
+
+    for _ in 0..samples {
+        counts[(rng.next_u64() >> (64 - bucket_bits)) as usize] += 1;
+    }
+
+    let expected = samples as f64 / counts.len() as f64;
+    let statistic = counts
+        .iter()
+        .map(|&count| {
+            let difference = count as f64 - expected;
+            difference * difference / expected
+        })
+        .sum();
+
+    TestResult {
+        name: "bucket chi-square",
+        statistic,
+        p_value: chi_square_upper_tail(statistic, (counts.len() - 1) as f64),
+    }
+}
+
+/// Birthday spacings test for a generator.
+///
+/// This is the variant described by Marsaglia with 512 birthdays in a year
+/// of 2<sup>24</sup> days taken from the top bits of the outputs.
+/// The number of duplicate spacings between the sorted birthdays is
+/// Poisson distributed with a mean of 2 in each repetition.
+/// The total over all `repetitions` is checked with a two-sided test.
+///
+/// # Panics
+///
+/// Panics if `repetitions` is 0.
+pub fn birthday_spacings<R: RngCore>(rng: &mut R, repetitions: u64) -> TestResult {
+    assert!(repetitions > 0, "repetitions must not be 0");
+
+    const BIRTHDAYS: usize = 512;
+    const DAY_BITS: u32 = 24;
+    const LAMBDA: f64 = (BIRTHDAYS * BIRTHDAYS * BIRTHDAYS) as f64 / (4 << DAY_BITS) as f64;
+
+    let mut birthdays = [0u64; BIRTHDAYS];
+    let mut spacings = [0u64; BIRTHDAYS];
+    let mut duplicates = 0u64;
+
+    for _ in 0..repetitions {
+        for birthday in birthdays.iter_mut() {
+            *birthday = rng.next_u64() >> (64 - DAY_BITS);
+        }
+
+        birthdays.sort_unstable();
+
+        spacings[0] = birthdays[0];
+        for index in 1..BIRTHDAYS {
+            spacings[index] = birthdays[index] - birthdays[index - 1];
+        }
+
+        spacings.sort_unstable();
+
+        duplicates += spacings
+            .windows(2)
+            .filter(|pair| pair[0] == pair[1])
+            .count() as u64;
+    }
+
+    let mean = LAMBDA * repetitions as f64;
+    let lower = poisson_cdf(duplicates, mean);
+    let upper = if duplicates == 0 {
+        1.0
+    } else {
+        1.0 - poisson_cdf(duplicates - 1, mean)
+    };
+    let p_value = (2.0 * lower.min(upper)).min(1.0);
+
+    TestResult {
+        name: "birthday spacings",
+        statistic: duplicates as f64,
+        p_value,
+    }
+}
+
+/// Gap test for a generator.
+///
+/// Outputs are converted to floats in [0, 1). The lengths of the gaps
+/// between outputs falling in [0, 1/4) follow a geometric distribution.
+/// `samples` gaps are counted in buckets of lengths 0 to 15 and 16 or more
+/// and checked with a chi-square test.
+///
+/// # Panics
+///
+/// Panics if `samples` is less than 1000.
+pub fn gap<R: RngCore>(rng: &mut R, samples: u64) -> TestResult {
+    assert!(samples >= 1000, "samples must be at least 1000");
+
+    const BUCKETS: usize = 17;
+    const PROBABILITY: f64 = 0.25;
+
+    let mut counts = [0u64; BUCKETS];
+
+    for _ in 0..samples {
+        let mut length = 0;
+
+        while to_unit_float(rng.next_u64()) >= PROBABILITY {
+            length += 1;
+        }
+
+        counts[length.min(BUCKETS - 1)] += 1;
+    }
+
+    let mut statistic = 0.0;
+
+    for (length, &count) in counts.iter().enumerate() {
+        let probability = if length == BUCKETS - 1 {
+            (1.0 - PROBABILITY).powi(length as i32)
+        } else {
+            PROBABILITY * (1.0 - PROBABILITY).powi(length as i32)
+        };
+        let expected = samples as f64 * probability;
+        let difference = count as f64 - expected;
+
+        statistic += difference * difference / expected;
+    }
+
+    TestResult {
+        name: "gap",
+        statistic,
+        p_value: chi_square_upper_tail(statistic, (BUCKETS - 1) as f64),
+    }
+}
+
+fn to_unit_float(value: u64) -> f64 {
+    (value >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+}
+
+fn binomial_chi_square(counts: &[u64], trials: u64, probability: f64) -> f64 {
+    let expected = trials as f64 * probability;
+    let variance = expected * (1.0 - probability);
+
+    counts
+        .iter()
+        .map(|&count| {
+            let difference = count as f64 - expected;
+            difference * difference / variance
+        })
+        .sum()
+}
+
+/// Returns the probability of a chi-square distributed variable with the
+/// given degrees of freedom being greater than or equal to `statistic`.
+pub fn chi_square_upper_tail(statistic: f64, degrees_of_freedom: f64) -> f64 {
+    upper_incomplete_gamma(degrees_of_freedom / 2.0, statistic / 2.0)
+}
+
+/// Returns the probability of a Poisson distributed variable with the
+/// given mean being less than or equal to `k`.
+pub fn poisson_cdf(k: u64, mean: f64) -> f64 {
+    upper_incomplete_gamma(k as f64 + 1.0, mean)
+}
+
+const MAX_ITERATIONS: usize = 1_000_000;
+const EPSILON: f64 = 1e-15;
+
+/// Regularized upper incomplete gamma function Q(a, x).
+fn upper_incomplete_gamma(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        1.0
+    } else if x < a + 1.0 {
+        1.0 - lower_incomplete_gamma_series(a, x)
+    } else {
+        upper_incomplete_gamma_fraction(a, x)
+    }
+}
+
+fn lower_incomplete_gamma_series(a: f64, x: f64) -> f64 {
+    let mut denominator = a;
+    let mut term = 1.0 / a;
+    let mut sum = term;
+
+    for _ in 0..MAX_ITERATIONS {
+        denominator += 1.0;
+        term *= x / denominator;
+        sum += term;
+
+        if term.abs() < sum.abs() * EPSILON {
+            break;
+        }
+    }
+
+    sum * (-x + a * x.ln() - ln_gamma(a)).exp()
+}
+
+fn upper_incomplete_gamma_fraction(a: f64, x: f64) -> f64 {
+    // Modified Lentz's method.
+    const TINY: f64 = 1e-300;
+
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / TINY;
+    let mut d = 1.0 / b;
+    let mut h = d;
+
+    for index in 1..MAX_ITERATIONS {
+        let an = -(index as f64) * (index as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = b + an / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+
+    (-x + a * x.ln() - ln_gamma(a)).exp() * h
+}
+
+fn ln_gamma(x: f64) -> f64 {
+    // Lanczos approximation with g = 7 and n = 9.
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        let pi = core::f64::consts::PI;
+        (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x)
+    } else {
+        let x = x - 1.0;
+        let t = x + 7.5;
+        let mut sum = COEFFICIENTS[0];
+
+        for (index, coefficient) in COEFFICIENTS.iter().enumerate().skip(1) {
+            sum += coefficient / (x + index as f64);
+        }
+
+        0.5 * (2.0 * core::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(value: f64, expected: f64) {
+        assert!(
+            (value - expected).abs() < 1e-6,
+            "{} is not close to {}",
+            value,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_chi_square_upper_tail() {
+        assert_close(chi_square_upper_tail(0.0, 10.0), 1.0);
+        assert_close(chi_square_upper_tail(3.841458820694124, 1.0), 0.05);
+        assert_close(chi_square_upper_tail(18.307038053275146, 10.0), 0.05);
+        assert_close(chi_square_upper_tail(4096.0, 4096.0), 0.497_061_4);
+    }
+
+    #[test]
+    fn test_poisson_cdf() {
+        assert_close(poisson_cdf(0, 2.0), (-2.0f64).exp());
+        assert_close(poisson_cdf(2, 2.0), 5.0 * (-2.0f64).exp());
+    }
+
+    #[test]
+    fn test_pair_index() {
+        let mut expected = 0;
+
+        for first in 0..64 {
+            for second in first + 1..64 {
+                assert_eq!(pair_index(first, second), expected);
+                expected += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn test_mixers_pass() {
+        for version in [Version::V1, Version::V2, Version::V3] {
+            let mut inputs = crate::v3::Mx3Rng::new(1);
+
+            let result = strict_avalanche(|x| version.mix(x), &mut inputs, 1000);
+            assert!(result.p_value > 1e-4, "{} {}", version, result);
+
+            let result = bit_independence(|x| version.mix(x), &mut inputs, 50);
+            assert!(result.p_value > 1e-4, "{} {}", version, result);
+        }
+    }
+
+    #[test]
+    fn test_weak_mixer_fails() {
+        let mut inputs = crate::v3::Mx3Rng::new(1);
+
+        let result = strict_avalanche(|x| x.wrapping_mul(0xbea225f9eb34556d), &mut inputs, 1000);
+        assert!(result.p_value < 1e-10, "{}", result);
+
+        let result = bit_independence(|x| x ^ (x >> 1), &mut inputs, 50);
+        assert!(result.p_value < 1e-10, "{}", result);
+    }
+
+    #[test]
+    fn test_generators_pass() {
+        for version in [Version::V1, Version::V2, Version::V3] {
+            let result = bucket_chi_square(&mut version.rng(1), 8, 100_000);
+            assert!(result.p_value > 1e-4, "{} {}", version, result);
+
+            let result = birthday_spacings(&mut version.rng(1), 100);
+            assert!(
+                result.p_value > 1e-4 && result.p_value <= 1.0,
+                "{} {}",
+                version,
+                result
+            );
+
+            let result = gap(&mut version.rng(1), 10_000);
+            assert!(result.p_value > 1e-4, "{} {}", version, result);
+        }
+    }
+
+    #[test]
+    fn test_weak_generator_fails() {
+        struct Counter(u64);
+
+        impl RngCore for Counter {
+            fn next_u32(&mut self) -> u32 {
+                self.next_u64() as u32
+            }
+
+            fn next_u64(&mut self) -> u64 {
+                self.0 = self.0.wrapping_add(0x0123_4567_89ab_cdef);
+                self.0
+            }
+
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                rand_core::impls::fill_bytes_via_next(self, dest);
+            }
+
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+                self.fill_bytes(dest);
+                Ok(())
+            }
+        }
+
+        let result = birthday_spacings(&mut Counter(0), 100);
+        assert!(result.p_value < 1e-10, "{}", result);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_too_few_samples() {
+        run_all(Version::V3, 0);
+    }
+
+    #[test]
+    fn test_minimum_samples() {
+        let mut inputs = crate::v3::Mx3Rng::new(1);
+
+        for result in [
+            strict_avalanche(|x| Version::V3.mix(x), &mut inputs, 10),
+            bit_independence(|x| Version::V3.mix(x), &mut inputs, 10),
+            bucket_chi_square(&mut inputs, 8, 5 << 8),
+            birthday_spacings(&mut inputs, 1),
+            gap(&mut inputs, 1000),
+        ] {
+            assert!((0.0..=1.0).contains(&result.p_value), "{}", result);
+        }
+    }
+
+    #[test]
+    fn test_run_all() {
+        let results = run_all(Version::V3, 100);
+        assert_eq!(results.len(), 5);
+
+        for result in results {
+            assert!((0.0..=1.0).contains(&result.p_value), "{}", result);
+        }
+    }
+}
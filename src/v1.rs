@@ -9,7 +9,10 @@ use core::fmt::{Debug, Formatter};
 
 use rand_core::{RngCore, SeedableRng};
 
+use crate::distributions;
+
 const PARAMETER_C: u64 = 0xbea225f9eb34556d;
+const PARAMETER_C_INVERSE: u64 = 0xdd01f46a7e6ffc65;
 
 /// Mix the bits in the integer.
 pub fn mix(mut x: u64) -> u64 {
@@ -22,6 +25,30 @@ pub fn mix(mut x: u64) -> u64 {
     x
 }
 
+/// Reverse the bits mixed by [`mix()`].
+///
+/// `unmix(mix(x)) == x` for every integer.
+pub fn unmix(mut x: u64) -> u64 {
+    x ^= x >> 39;
+    x = x.wrapping_mul(PARAMETER_C_INVERSE);
+    x = unxorshift(x, 29);
+    x = x.wrapping_mul(PARAMETER_C_INVERSE);
+    x ^= x >> 33;
+    x = x.wrapping_mul(PARAMETER_C_INVERSE);
+    x
+}
+
+fn unxorshift(mut x: u64, shift: u32) -> u64 {
+    let mut amount = shift;
+
+    while amount < 64 {
+        x ^= x >> amount;
+        amount *= 2;
+    }
+
+    x
+}
+
 fn mix_stream(mut h: u64, mut x: u64) -> u64 {
     x = x.wrapping_mul(PARAMETER_C);
     x ^= (x >> 57) ^ (x >> 33);
@@ -77,6 +104,9 @@ pub fn hash(buffer: &[u8], seed: u64) -> u64 {
 
 /// Pseudo-random number generator with 64-bits of state and cycle of 2^64.
 ///
+/// The sampling methods, such as [`Self::gen_range_u64()`], are
+/// [stable](crate#stability).
+///
 /// This RNG is *not* cryptographically secure.
 #[derive(Clone)]
 pub struct Mx3Rng {
@@ -101,6 +131,8 @@ impl Mx3Rng {
     }
 }
 
+distributions::impl_sampling_methods!(Mx3Rng, mix);
+
 impl SeedableRng for Mx3Rng {
     type Seed = [u8; 8];
 
@@ -147,6 +179,16 @@ mod tests {
         assert_eq!(mix(123456789), 0x566319fa1c03230f);
     }
 
+    #[test]
+    fn test_unmix() {
+        assert_eq!(unmix(mix(123456789)), 123456789);
+
+        for x in [0, 1, u64::MAX, PARAMETER_C, 1 << 63] {
+            assert_eq!(unmix(mix(x)), x);
+            assert_eq!(mix(unmix(x)), x);
+        }
+    }
+
     #[test]
     fn test_hash() {
         let input = b"abcdefghijklmnopqrstuvwxyz";
@@ -9,7 +9,19 @@ use core::fmt::{Debug, Formatter};
 
 use rand_core::{RngCore, SeedableRng};
 
+use crate::distributions;
+
+#[cfg(all(feature = "getrandom", feature = "std"))]
+mod thread_rng;
+
+#[cfg_attr(docsrs, doc(cfg(all(feature = "getrandom", feature = "std"))))]
+#[cfg(all(feature = "getrandom", feature = "std"))]
+pub use thread_rng::{thread_rng, ThreadRng};
+
 const PARAMETER_C: u64 = 0xbea225f9eb34556d;
+const PARAMETER_C_INVERSE: u64 = 0xdd01f46a7e6ffc65;
+const PARAMETER_C_32: u32 = PARAMETER_C as u32;
+const PARAMETER_C_32_INVERSE: u32 = 0x7e6ffc65;
 
 /// Mix the bits in the integer.
 pub fn mix(mut x: u64) -> u64 {
@@ -23,6 +35,138 @@ pub fn mix(mut x: u64) -> u64 {
     x
 }
 
+/// Reverse the bits mixed by [`mix()`].
+///
+/// `unmix(mix(x)) == x` for every integer.
+pub fn unmix(mut x: u64) -> u64 {
+    x = unxorshift(x, 29);
+    x = x.wrapping_mul(PARAMETER_C_INVERSE);
+    x ^= x >> 32;
+    x = x.wrapping_mul(PARAMETER_C_INVERSE);
+    x = unxorshift(x, 29);
+    x = x.wrapping_mul(PARAMETER_C_INVERSE);
+    x ^= x >> 32;
+    x
+}
+
+fn unxorshift(mut x: u64, shift: u32) -> u64 {
+    let mut amount = shift;
+
+    while amount < 64 {
+        x ^= x >> amount;
+        amount *= 2;
+    }
+
+    x
+}
+
+/// Mix the bits in the integer with a key.
+///
+/// This is an extension that is not part of the reference design. For each
+/// key, the function is a different bijection that can only be reversed
+/// using [`unmix_keyed()`] with the same key. It computes
+/// `mix(mix(x ^ k0) ^ k1) ^ k2` where the round key `ki` is
+/// `mix(key + (i + 1) * PARAMETER_C)` using wrapping arithmetic.
+///
+/// This function is *not* cryptographically secure: the key may be
+/// recovered from pairs of inputs and outputs.
+pub fn mix_keyed(x: u64, key: u64) -> u64 {
+    mix_round_keys(x, &round_keys(key))
+}
+
+/// Reverse the bits mixed by [`mix_keyed()`] with the same key.
+///
+/// `unmix_keyed(mix_keyed(x, key), key) == x` for every integer and key.
+pub fn unmix_keyed(x: u64, key: u64) -> u64 {
+    let [k0, k1, k2] = round_keys(key);
+
+    unmix(unmix(x ^ k2) ^ k1) ^ k0
+}
+
+/// Computes [`mix_keyed()`] with round keys from [`round_keys()`], so
+/// callers mixing many integers with one key only derive them once.
+pub(crate) fn mix_round_keys(x: u64, round_keys: &[u64; 3]) -> u64 {
+    let [k0, k1, k2] = *round_keys;
+
+    mix(mix(x ^ k0) ^ k1) ^ k2
+}
+
+pub(crate) fn round_keys(key: u64) -> [u64; 3] {
+    [
+        mix(key.wrapping_add(PARAMETER_C)),
+        mix(key.wrapping_add(PARAMETER_C.wrapping_mul(2))),
+        mix(key.wrapping_add(PARAMETER_C.wrapping_mul(3))),
+    ]
+}
+
+/// Mix the bits in the 32-bit integer.
+///
+/// This is an extension that is not part of the reference design. It has
+/// the same structure as [`mix()`] with the shifts halved and the lower 32
+/// bits of the constant.
+pub fn mix32(mut x: u32) -> u32 {
+    x ^= x >> 16;
+    x = x.wrapping_mul(PARAMETER_C_32);
+    x ^= x >> 15;
+    x = x.wrapping_mul(PARAMETER_C_32);
+    x ^= x >> 16;
+    x = x.wrapping_mul(PARAMETER_C_32);
+    x ^= x >> 15;
+    x
+}
+
+/// Reverse the bits mixed by [`mix32()`].
+///
+/// `unmix32(mix32(x)) == x` for every integer.
+pub fn unmix32(mut x: u32) -> u32 {
+    x = unxorshift32(x, 15);
+    x = x.wrapping_mul(PARAMETER_C_32_INVERSE);
+    x ^= x >> 16;
+    x = x.wrapping_mul(PARAMETER_C_32_INVERSE);
+    x = unxorshift32(x, 15);
+    x = x.wrapping_mul(PARAMETER_C_32_INVERSE);
+    x ^= x >> 16;
+    x
+}
+
+fn unxorshift32(mut x: u32, shift: u32) -> u32 {
+    let mut amount = shift;
+
+    while amount < 32 {
+        x ^= x >> amount;
+        amount *= 2;
+    }
+
+    x
+}
+
+/// Mix the bits in the 128-bit integer.
+///
+/// This is an extension that is not part of the reference design. With the
+/// upper and lower halves of the input as `hi` and `lo`, it computes
+/// `a = mix(hi)`, `b = mix(lo ^ a)`, and `c = mix(a ^ b)` and returns `c` as
+/// the upper half and `b` as the lower half, so every input bit affects
+/// every output bit.
+pub fn mix128(x: u128) -> u128 {
+    let a = mix((x >> 64) as u64);
+    let b = mix(x as u64 ^ a);
+    let c = mix(a ^ b);
+
+    (c as u128) << 64 | b as u128
+}
+
+/// Reverse the bits mixed by [`mix128()`].
+///
+/// `unmix128(mix128(x)) == x` for every integer.
+pub fn unmix128(x: u128) -> u128 {
+    let b = x as u64;
+    let a = unmix((x >> 64) as u64) ^ b;
+    let lo = unmix(b) ^ a;
+    let hi = unmix(a);
+
+    (hi as u128) << 64 | lo as u128
+}
+
 fn mix_stream_2(mut h: u64, mut x: u64) -> u64 {
     x = x.wrapping_mul(PARAMETER_C);
     x ^= x >> 39;
@@ -152,6 +296,9 @@ pub fn hash(buffer: &[u8], seed: u64) -> u64 {
 
 /// Pseudo-random number generator with 64-bits of state and cycle of 2^64.
 ///
+/// The sampling methods, such as [`Self::gen_range_u64()`], are
+/// [stable](crate#stability).
+///
 /// This RNG is *not* cryptographically secure.
 #[derive(Clone)]
 pub struct Mx3Rng {
@@ -184,6 +331,8 @@ impl Mx3Rng {
     }
 }
 
+distributions::impl_sampling_methods!(Mx3Rng, mix);
+
 impl SeedableRng for Mx3Rng {
     type Seed = [u8; 8];
 
@@ -230,6 +379,113 @@ mod tests {
         assert_eq!(mix(123456789), 0x95bd1de6327dae0a);
     }
 
+    #[test]
+    fn test_unmix() {
+        assert_eq!(unmix(mix(123456789)), 123456789);
+
+        for x in [0, 1, u64::MAX, PARAMETER_C, 1 << 63] {
+            assert_eq!(unmix(mix(x)), x);
+            assert_eq!(mix(unmix(x)), x);
+        }
+    }
+
+    #[test]
+    fn test_mix_keyed() {
+        assert_eq!(mix_keyed(123456789, 1), 0x9d376af008bf9b97);
+
+        for key in [0, 1, 2, u64::MAX] {
+            for x in (0..1000).chain([u64::MAX]) {
+                assert_eq!(unmix_keyed(mix_keyed(x, key), key), x);
+            }
+        }
+
+        assert_ne!(mix_keyed(1, 1), mix_keyed(1, 2));
+        assert_ne!(unmix_keyed(mix_keyed(1, 1), 2), 1);
+    }
+
+    #[test]
+    fn test_mix_keyed_key_avalanche() {
+        let mut rng = Mx3Rng::new(4);
+        let mut flips = [0u32; 64];
+
+        for _ in 0..10000 {
+            let x = rng.next_u64();
+            let key = rng.next_u64();
+
+            for (bit, count) in flips.iter_mut().enumerate() {
+                *count += (mix_keyed(x, key) ^ mix_keyed(x, key ^ 1 << bit)).count_ones();
+            }
+        }
+
+        for count in flips {
+            let average = count as f64 / 10000.0;
+            assert!((31.5..32.5).contains(&average), "{:?}", flips);
+        }
+    }
+
+    #[test]
+    fn test_mix32() {
+        assert_eq!(mix32(0), 0);
+        assert_eq!(mix32(123456789), 0xe6706d71);
+
+        for x in (0..100000u32).chain([u32::MAX - 1, u32::MAX]) {
+            assert_eq!(unmix32(mix32(x)), x);
+        }
+    }
+
+    #[test]
+    fn test_mix128() {
+        assert_eq!(mix128(0), 0);
+        assert_eq!(mix128(123456789), 0x2739643534c4fcf195bd1de6327dae0a);
+
+        let mut rng = Mx3Rng::new(1);
+
+        for _ in 0..10000 {
+            let x = (rng.next_u64() as u128) << 64 | rng.next_u64() as u128;
+            assert_eq!(unmix128(mix128(x)), x);
+        }
+
+        assert_eq!(unmix128(mix128(u128::MAX)), u128::MAX);
+    }
+
+    #[test]
+    fn test_mix32_avalanche() {
+        let mut rng = Mx3Rng::new(2);
+        let mut flips = [0u32; 32];
+
+        for _ in 0..10000 {
+            let x = rng.next_u32();
+
+            for (bit, count) in flips.iter_mut().enumerate() {
+                *count += (mix32(x) ^ mix32(x ^ 1 << bit)).count_ones();
+            }
+        }
+
+        for count in flips {
+            let average = count as f64 / 10000.0;
+            assert!((15.5..16.5).contains(&average), "{:?}", flips);
+        }
+    }
+
+    #[test]
+    fn test_mix128_avalanche() {
+        let mut rng = Mx3Rng::new(3);
+        let mut flips = [0u32; 128];
+
+        for _ in 0..10000 {
+            let x = (rng.next_u64() as u128) << 64 | rng.next_u64() as u128;
+
+            for (bit, count) in flips.iter_mut().enumerate() {
+                *count += (mix128(x) ^ mix128(x ^ 1 << bit)).count_ones();
+            }
+        }
+
+        for count in flips {
+            let average = count as f64 / 10000.0;
+            assert!((63.0..65.0).contains(&average), "{:?}", flips);
+        }
+    }
+
     #[test]
     fn test_hash() {
         let input = b"abcdefghijklmnopqrstuvwxyz";
@@ -289,6 +545,15 @@ mod tests {
         assert_eq!(rng.next_u32(), 0x25a1_74d9);
     }
 
+    #[cfg(feature = "getrandom")]
+    #[test]
+    fn test_from_entropy() {
+        let mut rng1 = Mx3Rng::from_entropy();
+        let mut rng2 = Mx3Rng::from_entropy();
+
+        assert_ne!(rng1.next_u64(), rng2.next_u64());
+    }
+
     #[test]
     fn test_debug() {
         let rng = Mx3Rng::new(1);
@@ -0,0 +1,128 @@
+use core::cell::RefCell;
+use core::fmt::{Debug, Formatter};
+use std::rc::Rc;
+
+use rand_core::{RngCore, SeedableRng};
+
+use super::Mx3Rng;
+
+struct State {
+    rng: Mx3Rng,
+    process_id: u32,
+}
+
+impl State {
+    fn new() -> Self {
+        Self {
+            rng: Mx3Rng::from_entropy(),
+            process_id: std::process::id(),
+        }
+    }
+
+    /// Returns the generator, reseeding it if the process was forked so
+    /// that the parent and child do not produce the same numbers.
+    fn rng(&mut self) -> &mut Mx3Rng {
+        if self.process_id != std::process::id() {
+            *self = Self::new();
+        }
+
+        &mut self.rng
+    }
+}
+
+std::thread_local! {
+    static THREAD_RNG: Rc<RefCell<State>> = Rc::new(RefCell::new(State::new()));
+}
+
+/// Returns a handle to the generator of the current thread.
+///
+/// The generator is seeded using [`SeedableRng::from_entropy()`] when it is
+/// first used in a thread. It is seeded again if the process ID changes,
+/// such as in the child process after a fork.
+///
+/// ```rust
+/// use rand::RngCore;
+///
+/// let value = mx3::v3::thread_rng().next_u64();
+/// ```
+///
+/// # Panics
+///
+/// Panics if the operating system's random number source is unavailable.
+pub fn thread_rng() -> ThreadRng {
+    ThreadRng {
+        state: THREAD_RNG.with(Rc::clone),
+    }
+}
+
+/// Handle to the generator of a thread returned by [`thread_rng()`].
+///
+/// This RNG is *not* cryptographically secure.
+#[derive(Clone)]
+pub struct ThreadRng {
+    state: Rc<RefCell<State>>,
+}
+
+impl RngCore for ThreadRng {
+    fn next_u32(&mut self) -> u32 {
+        self.state.borrow_mut().rng().next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state.borrow_mut().rng().next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.state.borrow_mut().rng().fill_bytes(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl Debug for ThreadRng {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "ThreadRng {{...}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_within_thread() {
+        let mut rng1 = thread_rng();
+        let mut rng2 = thread_rng();
+
+        let state = rng1.state.borrow().rng.state();
+        rng1.next_u64();
+        rng2.next_u64();
+
+        assert_eq!(rng1.state.borrow().rng.state(), state.wrapping_add(2));
+    }
+
+    #[test]
+    fn test_differs_between_threads() {
+        let value = thread_rng().next_u64();
+        let other_value = std::thread::spawn(|| thread_rng().next_u64())
+            .join()
+            .unwrap();
+
+        assert_ne!(value, other_value);
+    }
+
+    #[test]
+    fn test_reseed_on_process_id_change() {
+        let mut rng = thread_rng();
+        rng.state.borrow_mut().process_id = std::process::id().wrapping_add(1);
+        let state = rng.state.borrow().rng.state();
+
+        rng.next_u64();
+
+        assert_eq!(rng.state.borrow().process_id, std::process::id());
+        assert_ne!(rng.state.borrow().rng.state(), state.wrapping_add(1));
+    }
+}
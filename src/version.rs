@@ -0,0 +1,266 @@
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+
+use rand_core::RngCore;
+
+use crate::{v1, v2, v3};
+
+/// Version of the mx3 algorithm selectable at runtime.
+///
+/// This is useful when the version is stored alongside persisted data
+/// and the matching algorithm must be chosen dynamically.
+/// If the version is known at compile time,
+/// use the functions in the version modules directly instead.
+///
+/// The textual representation is `v1`, `v2`, or `v3`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Version {
+    /// Version 1 ([`crate::v1`]).
+    V1,
+    /// Version 2 ([`crate::v2`]).
+    V2,
+    /// Version 3 ([`crate::v3`]).
+    V3,
+}
+
+impl Version {
+    /// Mix the bits in the integer using this version.
+    pub fn mix(self, x: u64) -> u64 {
+        match self {
+            Self::V1 => v1::mix(x),
+            Self::V2 => v2::mix(x),
+            Self::V3 => v3::mix(x),
+        }
+    }
+
+    /// Reverse the bits mixed by [`Self::mix()`] using this version.
+    pub fn unmix(self, x: u64) -> u64 {
+        match self {
+            Self::V1 => v1::unmix(x),
+            Self::V2 => v2::unmix(x),
+            Self::V3 => v3::unmix(x),
+        }
+    }
+
+    /// Hash the given buffer using this version.
+    ///
+    /// This hasher is *not* cryptographically secure.
+    pub fn hash(self, buffer: &[u8], seed: u64) -> u64 {
+        match self {
+            Self::V1 => v1::hash(buffer, seed),
+            Self::V2 => v2::hash(buffer, seed),
+            Self::V3 => v3::hash(buffer, seed),
+        }
+    }
+
+    /// Creates the PRNG generator of this version using the given seed.
+    ///
+    /// The seed is handled the same way as the `new()` constructor of the
+    /// version's `Mx3Rng`.
+    pub fn rng(self, seed: u64) -> VersionedRng {
+        match self {
+            Self::V1 => VersionedRng::V1(v1::Mx3Rng::new(seed)),
+            Self::V2 => VersionedRng::V2(v2::Mx3Rng::new(seed)),
+            Self::V3 => VersionedRng::V3(v3::Mx3Rng::new(seed)),
+        }
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let text = match self {
+            Self::V1 => "v1",
+            Self::V2 => "v2",
+            Self::V3 => "v3",
+        };
+
+        f.write_str(text)
+    }
+}
+
+impl FromStr for Version {
+    type Err = ParseVersionError;
+
+    /// Parses `v1`, `v2`, or `v3`.
+    ///
+    /// The prefix is case insensitive and may be omitted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = s
+            .strip_prefix('v')
+            .or_else(|| s.strip_prefix('V'))
+            .unwrap_or(s);
+
+        match number {
+            "1" => Ok(Self::V1),
+            "2" => Ok(Self::V2),
+            "3" => Ok(Self::V3),
+            _ => Err(ParseVersionError),
+        }
+    }
+}
+
+/// Error returned when parsing a [`Version`] from a string fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseVersionError;
+
+impl Display for ParseVersionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("unknown mx3 version")
+    }
+}
+
+/// Pseudo-random number generator of a [`Version`] selected at runtime.
+///
+/// This RNG is *not* cryptographically secure.
+#[derive(Clone, Debug)]
+pub enum VersionedRng {
+    /// Version 1 generator.
+    V1(v1::Mx3Rng),
+    /// Version 2 generator.
+    V2(v2::Mx3Rng),
+    /// Version 3 generator.
+    V3(v3::Mx3Rng),
+}
+
+impl VersionedRng {
+    /// Returns the version of the generator.
+    pub fn version(&self) -> Version {
+        match self {
+            Self::V1(_) => Version::V1,
+            Self::V2(_) => Version::V2,
+            Self::V3(_) => Version::V3,
+        }
+    }
+
+    /// Returns the state of the generator.
+    ///
+    /// See the `state()` method of the version's `Mx3Rng` for how to
+    /// resume the generator.
+    pub fn state(&self) -> u64 {
+        match self {
+            Self::V1(rng) => rng.state(),
+            Self::V2(rng) => rng.state(),
+            Self::V3(rng) => rng.state(),
+        }
+    }
+}
+
+impl RngCore for VersionedRng {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            Self::V1(rng) => rng.next_u32(),
+            Self::V2(rng) => rng.next_u32(),
+            Self::V3(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            Self::V1(rng) => rng.next_u64(),
+            Self::V2(rng) => rng.next_u64(),
+            Self::V3(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            Self::V1(rng) => rng.fill_bytes(dest),
+            Self::V2(rng) => rng.fill_bytes(dest),
+            Self::V3(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl From<v1::Mx3Rng> for VersionedRng {
+    fn from(rng: v1::Mx3Rng) -> Self {
+        Self::V1(rng)
+    }
+}
+
+impl From<v2::Mx3Rng> for VersionedRng {
+    fn from(rng: v2::Mx3Rng) -> Self {
+        Self::V2(rng)
+    }
+}
+
+impl From<v3::Mx3Rng> for VersionedRng {
+    fn from(rng: v3::Mx3Rng) -> Self {
+        Self::V3(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mix() {
+        assert_eq!(Version::V1.mix(123456789), v1::mix(123456789));
+        assert_eq!(Version::V2.mix(123456789), v2::mix(123456789));
+        assert_eq!(Version::V3.mix(123456789), v3::mix(123456789));
+    }
+
+    #[test]
+    fn test_unmix() {
+        for version in [Version::V1, Version::V2, Version::V3] {
+            assert_eq!(version.unmix(version.mix(123456789)), 123456789);
+        }
+    }
+
+    #[test]
+    fn test_hash() {
+        let input = b"abcdefghijklmnopqrstuvwxyz";
+
+        assert_eq!(
+            Version::V1.hash(input, 123456789),
+            v1::hash(input, 123456789)
+        );
+        assert_eq!(
+            Version::V2.hash(input, 123456789),
+            v2::hash(input, 123456789)
+        );
+        assert_eq!(
+            Version::V3.hash(input, 123456789),
+            v3::hash(input, 123456789)
+        );
+    }
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Version::V1.rng(1);
+        assert_eq!(rng.version(), Version::V1);
+        assert_eq!(rng.next_u64(), v1::Mx3Rng::new(1).next_u64());
+
+        let mut rng = Version::V2.rng(1);
+        assert_eq!(rng.version(), Version::V2);
+        assert_eq!(rng.next_u64(), v2::Mx3Rng::new(1).next_u64());
+
+        let mut rng = Version::V3.rng(1);
+        assert_eq!(rng.version(), Version::V3);
+        assert_eq!(rng.next_u64(), v3::Mx3Rng::new(1).next_u64());
+        assert_eq!(rng.state(), v3::Mx3Rng::new(1).state().wrapping_add(1));
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("v1".parse(), Ok(Version::V1));
+        assert_eq!("V2".parse(), Ok(Version::V2));
+        assert_eq!("3".parse(), Ok(Version::V3));
+        assert_eq!("v4".parse::<Version>(), Err(ParseVersionError));
+        assert_eq!("".parse::<Version>(), Err(ParseVersionError));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        use std::string::ToString;
+
+        for version in [Version::V1, Version::V2, Version::V3] {
+            assert_eq!(version.to_string().parse(), Ok(version));
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Property-based tests.
+
+use mx3::{v1, v2, v3, Version};
+use proptest::prelude::*;
+use rand::RngCore;
+
+fn version_strategy() -> impl Strategy<Value = Version> {
+    prop_oneof![Just(Version::V1), Just(Version::V2), Just(Version::V3)]
+}
+
+proptest! {
+    #[test]
+    fn hash_does_not_panic(
+        version in version_strategy(),
+        buffer in proptest::collection::vec(any::<u8>(), 0..4096),
+        seed in any::<u64>(),
+    ) {
+        version.hash(&buffer, seed);
+    }
+
+    #[test]
+    fn unmix_reverses_mix(version in version_strategy(), x in any::<u64>()) {
+        prop_assert_eq!(version.unmix(version.mix(x)), x);
+        prop_assert_eq!(version.mix(version.unmix(x)), x);
+    }
+
+    #[test]
+    fn unmix_keyed_reverses_mix_keyed(x in any::<u64>(), key in any::<u64>()) {
+        prop_assert_eq!(v3::unmix_keyed(v3::mix_keyed(x, key), key), x);
+        prop_assert_eq!(v3::mix_keyed(v3::unmix_keyed(x, key), key), x);
+    }
+
+    #[test]
+    fn unmix32_reverses_mix32(x in any::<u32>()) {
+        prop_assert_eq!(v3::unmix32(v3::mix32(x)), x);
+        prop_assert_eq!(v3::mix32(v3::unmix32(x)), x);
+    }
+
+    #[test]
+    fn unmix128_reverses_mix128(x in any::<u128>()) {
+        prop_assert_eq!(v3::unmix128(v3::mix128(x)), x);
+        prop_assert_eq!(v3::mix128(v3::unmix128(x)), x);
+    }
+
+    #[test]
+    fn rng_iter_nth_agrees_with_stepping(seed in any::<u64>(), steps in 0usize..512) {
+        let mut rng1 = v1::Mx3Rng::new(seed);
+        let mut rng2 = v2::Mx3Rng::new(seed);
+        let mut rng3 = v3::Mx3Rng::new(seed);
+
+        let mut skipped1 = rng1.clone();
+        let mut skipped2 = rng2.clone();
+        let mut skipped3 = rng3.clone();
+
+        for _ in 0..steps {
+            rng1.next_u64();
+            rng2.next_u64();
+            rng3.next_u64();
+        }
+
+        prop_assert_eq!(skipped1.iter_u64().nth(steps), Some(rng1.next_u64()));
+        prop_assert_eq!(skipped2.iter_u64().nth(steps), Some(rng2.next_u64()));
+        prop_assert_eq!(skipped3.iter_u64().nth(steps), Some(rng3.next_u64()));
+        prop_assert_eq!(skipped1.next_u64(), rng1.next_u64());
+        prop_assert_eq!(skipped2.next_u64(), rng2.next_u64());
+        prop_assert_eq!(skipped3.next_u64(), rng3.next_u64());
+    }
+}
+
+#[cfg(feature = "hasher")]
+proptest! {
+    #[test]
+    fn hasher_split_writes_agree(
+        buffer in proptest::collection::vec(any::<u8>(), 0..4096),
+        splits in proptest::collection::vec(any::<prop::sample::Index>(), 0..8),
+        seed in any::<u64>(),
+    ) {
+        use std::hash::Hasher;
+
+        let mut one_shot = mx3::Mx3Hasher::new(seed);
+        one_shot.write(&buffer);
+
+        let mut points: Vec<usize> = splits.iter().map(|index| index.index(buffer.len() + 1)).collect();
+        points.sort_unstable();
+
+        let mut streaming = mx3::Mx3Hasher::new(seed);
+        let mut start = 0;
+
+        for point in points {
+            streaming.write(&buffer[start..point]);
+            start = point;
+        }
+
+        streaming.write(&buffer[start..]);
+
+        prop_assert_eq!(streaming.finish(), one_shot.finish());
+    }
+}
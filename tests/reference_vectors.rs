@@ -0,0 +1,101 @@
+//! Checks the implementations against the test vectors in `tests/vectors`.
+//!
+//! The vector files are generated by `tests/vectors/generate.cpp`. The
+//! header of each file records the tag and commit of the reference design
+//! it was generated from.
+
+use mx3::Version;
+use rand::RngCore;
+
+const MAX_LENGTH: usize = 1024;
+
+fn fill_buffer(buffer: &mut [u8]) {
+    let mut state = 0u64;
+
+    for value in buffer.iter_mut() {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        *value = (state >> 56) as u8;
+    }
+}
+
+fn parse_hex(text: &str) -> u64 {
+    u64::from_str_radix(text, 16).unwrap()
+}
+
+fn check_vectors(version: Version, vectors: &str) {
+    let mut buffer = [0u8; MAX_LENGTH];
+    fill_buffer(&mut buffer);
+
+    let mut rng = version.rng(0);
+    let mut counts = [0usize; 3];
+
+    for (line_index, line) in vectors.lines().enumerate() {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split(' ').collect();
+        let line_number = line_index + 1;
+
+        match fields.as_slice() {
+            ["mix", input, output] => {
+                assert_eq!(
+                    version.mix(parse_hex(input)),
+                    parse_hex(output),
+                    "{} line {}",
+                    version,
+                    line_number
+                );
+                counts[0] += 1;
+            }
+            ["hash", seed, length, output] => {
+                let length = parse_hex(length) as usize;
+                assert_eq!(
+                    version.hash(&buffer[0..length], parse_hex(seed)),
+                    parse_hex(output),
+                    "{} line {}",
+                    version,
+                    line_number
+                );
+                counts[1] += 1;
+            }
+            ["rng", seed, index, output] => {
+                if parse_hex(index) == 0 {
+                    rng = version.rng(parse_hex(seed));
+                }
+                assert_eq!(
+                    rng.next_u64(),
+                    parse_hex(output),
+                    "{} line {}",
+                    version,
+                    line_number
+                );
+                counts[2] += 1;
+            }
+            _ => panic!("{} line {}: unknown record", version, line_number),
+        }
+    }
+
+    assert!(
+        counts.iter().all(|&count| count > 0),
+        "{} vectors are incomplete",
+        version
+    );
+}
+
+#[test]
+fn test_v1_vectors() {
+    check_vectors(Version::V1, include_str!("vectors/v1.txt"));
+}
+
+#[test]
+fn test_v2_vectors() {
+    check_vectors(Version::V2, include_str!("vectors/v2.txt"));
+}
+
+#[test]
+fn test_v3_vectors() {
+    check_vectors(Version::V3, include_str!("vectors/v3.txt"));
+}
@@ -0,0 +1,100 @@
+// Program that generates the reference test vectors from the reference design.
+//
+// Compile it against the `mx3.h` of the tag being generated and write
+// the output to the vector file of the matching version, for example:
+//
+//     git -C mx3 checkout v3.0.0
+//     g++ -O2 -std=c++11 -I mx3 generate.cpp -o generate
+//     ./generate v3.0.0 "$(git -C mx3 rev-parse HEAD)" > v3.txt
+//
+// `regenerate.sh` does this for every version from a fresh clone of the
+// upstream repository.
+//
+// The header records the tag and commit of the reference design:
+//
+//     # mx3 reference test vectors
+//     # tag: <tag>
+//     # commit: <commit>
+//
+// Each line is a record of space separated fields with integers in hex:
+//
+//     mix <input> <output>
+//     hash <seed> <length> <output>
+//     rng <seed> <index> <output>
+//
+// The hash input is the prefix of the given length of a buffer filled by
+// `fill_buffer()` which the test must reproduce.
+
+#include <cstdint>
+#include <cstdio>
+#include <inttypes.h>
+#include "mx3.h"
+
+static const size_t MAX_LENGTH = 1024;
+static const size_t RNG_OUTPUTS = 64;
+
+static const uint64_t SEEDS[] = {
+    0,
+    1,
+    123456789,
+    0xffffffffffffffffull,
+};
+
+static void fill_buffer(uint8_t *buffer, size_t length) {
+    uint64_t state = 0;
+
+    for (size_t i = 0; i < length; i++) {
+        state = state * 6364136223846793005ull + 1442695040888963407ull;
+        buffer[i] = (uint8_t)(state >> 56);
+    }
+}
+
+static void print_mix(uint64_t x) {
+    printf("mix %" PRIx64 " %" PRIx64 "\n", x, mx3::mix(x));
+}
+
+int main(int argc, char *argv[]) {
+    if (argc != 3) {
+        fprintf(stderr, "usage: %s <tag> <commit>\n", argv[0]);
+        return 1;
+    }
+
+    printf("# mx3 reference test vectors\n");
+    printf("# tag: %s\n", argv[1]);
+    printf("# commit: %s\n", argv[2]);
+
+    for (uint64_t x = 0; x < 64; x++) {
+        print_mix(x);
+    }
+
+    for (int bit = 6; bit < 64; bit++) {
+        print_mix(1ull << bit);
+        print_mix(~(1ull << bit));
+    }
+
+    uint64_t x = 0;
+    for (int i = 0; i < 64; i++) {
+        x = x * 6364136223846793005ull + 1442695040888963407ull;
+        print_mix(x);
+    }
+
+    uint8_t buffer[MAX_LENGTH];
+    fill_buffer(buffer, MAX_LENGTH);
+
+    for (uint64_t seed : SEEDS) {
+        for (size_t length = 0; length <= MAX_LENGTH; length++) {
+            printf("hash %" PRIx64 " %zx %" PRIx64 "\n",
+                seed, length, mx3::hash(buffer, length, seed));
+        }
+    }
+
+    for (uint64_t seed : SEEDS) {
+        mx3::random rng(seed);
+
+        for (size_t index = 0; index < RNG_OUTPUTS; index++) {
+            printf("rng %" PRIx64 " %zx %" PRIx64 "\n", seed, index, rng());
+        }
+    }
+
+    return 0;
+}
@@ -0,0 +1,21 @@
+#!/bin/sh
+# Regenerates the reference test vectors from the upstream repository.
+#
+# Usage: tests/vectors/regenerate.sh [output directory]
+set -eu
+
+cd "$(dirname "$0")"
+output=$(cd "${1:-.}" && pwd)
+work=$(mktemp -d)
+trap 'rm -rf "$work"' EXIT
+
+git clone --quiet https://github.com/jonmaiga/mx3 "$work/mx3"
+
+for version in 1 2 3; do
+    tag="v$version.0.0"
+    git -C "$work/mx3" checkout --quiet "$tag"
+    commit=$(git -C "$work/mx3" rev-parse HEAD)
+
+    ${CXX:-c++} -O2 -std=c++11 -I "$work/mx3" generate.cpp -o "$work/generate"
+    "$work/generate" "$tag" "$commit" > "$output/v$version.txt"
+done
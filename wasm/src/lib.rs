@@ -0,0 +1,89 @@
+//! WebAssembly bindings for the mx3 crate.
+//!
+//! The bindings use version 3 of the algorithm and produce the same outputs
+//! as [`mx3::v3`]. 64-bit integers are `BigInt` values in JavaScript.
+//!
+//! ```js
+//! import { hash, mix, Mx3Rng } from "mx3-wasm";
+//!
+//! const digest = hash(new TextEncoder().encode("Hello world!"), 123456789n);
+//! const rng = new Mx3Rng(1n);
+//! console.log(digest.toString(16), mix(123456789n), rng.nextU64());
+//! ```
+
+use js_sys::Uint8Array;
+use rand_core::RngCore;
+use wasm_bindgen::prelude::*;
+
+/// Mix the bits in the integer.
+#[wasm_bindgen]
+pub fn mix(x: u64) -> u64 {
+    mx3::v3::mix(x)
+}
+
+/// Reverse the bits mixed by `mix()`.
+#[wasm_bindgen]
+pub fn unmix(x: u64) -> u64 {
+    mx3::v3::unmix(x)
+}
+
+/// Hash the bytes in the given array.
+///
+/// This hasher is *not* cryptographically secure.
+#[wasm_bindgen]
+pub fn hash(buffer: &Uint8Array, seed: u64) -> u64 {
+    mx3::v3::hash(&buffer.to_vec(), seed)
+}
+
+/// Pseudo-random number generator with 64-bits of state and cycle of 2^64.
+///
+/// This RNG is *not* cryptographically secure.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct Mx3Rng {
+    inner: mx3::v3::Mx3Rng,
+}
+
+#[wasm_bindgen]
+impl Mx3Rng {
+    /// Creates the PRNG generator using the given seed.
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            inner: mx3::v3::Mx3Rng::new(seed),
+        }
+    }
+
+    /// Creates the PRNG generator from an existing state.
+    pub fn resume(state: u64) -> Self {
+        Self {
+            inner: mx3::v3::Mx3Rng::resume(state),
+        }
+    }
+
+    /// Returns the state of the generator.
+    ///
+    /// The generator can be resumed by passing the state to `resume()`.
+    #[wasm_bindgen(getter)]
+    pub fn state(&self) -> u64 {
+        self.inner.state()
+    }
+
+    /// Returns the next random 64-bit integer.
+    #[wasm_bindgen(js_name = nextU64)]
+    pub fn next_u64(&mut self) -> u64 {
+        self.inner.next_u64()
+    }
+
+    /// Returns the next random 32-bit integer.
+    #[wasm_bindgen(js_name = nextU32)]
+    pub fn next_u32(&mut self) -> u32 {
+        self.inner.next_u32()
+    }
+
+    /// Fills the buffer with random bytes.
+    #[wasm_bindgen(js_name = fillBytes)]
+    pub fn fill_bytes(&mut self, buffer: &mut [u8]) {
+        self.inner.fill_bytes(buffer);
+    }
+}
@@ -0,0 +1,58 @@
+//! Tests running in Node.js.
+//!
+//! Run them using `cargo test --target wasm32-unknown-unknown` in this
+//! directory with `wasm-bindgen-cli` installed.
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::{BigInt, Uint8Array};
+use mx3_wasm::*;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+fn test_mix() {
+    assert_eq!(mix(123456789), 0x95bd1de6327dae0a);
+    assert_eq!(unmix(0x95bd1de6327dae0a), 123456789);
+}
+
+#[wasm_bindgen_test]
+fn test_hash() {
+    let array = Uint8Array::from(&b"abcdefghijklmnopqrstuvwxyz"[..]);
+
+    assert_eq!(hash(&array, 123456789), 0x6c16bdf4571e7844);
+    assert_eq!(
+        hash(&Uint8Array::new_with_length(0), 123456789),
+        0x4e069d451e12ced8
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_hash_uint8_array_view() {
+    let array = Uint8Array::new_with_length(28);
+    for (index, &value) in b"abcdefghijklmnopqrstuvwxyz".iter().enumerate() {
+        array.set_index(index as u32 + 1, value);
+    }
+
+    assert_eq!(hash(&array.subarray(1, 27), 123456789), 0x6c16bdf4571e7844);
+}
+
+#[wasm_bindgen_test]
+fn test_bigint() {
+    let value = JsValue::from(mix(123456789));
+    let expected = BigInt::from(0x95bd1de6327dae0au64);
+
+    assert_eq!(value, JsValue::from(expected));
+}
+
+#[wasm_bindgen_test]
+fn test_rng() {
+    let mut rng = Mx3Rng::new(1);
+    assert_eq!(rng.next_u64(), 0xe8eb_dbc4_39df_412a);
+
+    let mut resumed = Mx3Rng::resume(rng.state());
+    assert_eq!(resumed.next_u32(), 0x25a1_74d9);
+
+    let mut buffer = [0u8; 8];
+    Mx3Rng::new(1).fill_bytes(&mut buffer);
+    assert_eq!(u64::from_le_bytes(buffer), 0xe8eb_dbc4_39df_412a);
+}